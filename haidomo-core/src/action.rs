@@ -0,0 +1,37 @@
+/// What the timer can be told to do, bound to keys by the frontends
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    StartOrSplit,
+    Pause,
+    ToggleGameTime,
+    Reset,
+    Undo,
+    Skip,
+    NextComparison,
+    PrevComparison,
+}
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::StartOrSplit,
+        Action::Pause,
+        Action::ToggleGameTime,
+        Action::Reset,
+        Action::Undo,
+        Action::Skip,
+        Action::NextComparison,
+        Action::PrevComparison,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::StartOrSplit => "Start / Split",
+            Action::Pause => "Start / Pause",
+            Action::ToggleGameTime => "Pause Game Time",
+            Action::Reset => "Reset",
+            Action::Undo => "Undo Split",
+            Action::Skip => "Skip Split",
+            Action::NextComparison => "Next Comparison",
+            Action::PrevComparison => "Previous Comparison",
+        }
+    }
+}
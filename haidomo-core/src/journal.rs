@@ -0,0 +1,530 @@
+use crate::splits_file::RunData;
+use crate::stopwatch::{Clock, Timestamp, TimingMethod};
+use crate::timer::{EventKind, Timer};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const HEADER: &str = "haidomo-journal 1";
+
+/// What a journal entry records, the timer events along with game time pauses
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JournalKind {
+    Event(EventKind),
+    GameTimePause,
+    GameTimeResume,
+}
+impl JournalKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JournalKind::Event(kind) => kind.name(),
+            JournalKind::GameTimePause => "game_time_pause",
+            JournalKind::GameTimeResume => "game_time_resume",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "game_time_pause" => Some(JournalKind::GameTimePause),
+            "game_time_resume" => Some(JournalKind::GameTimeResume),
+            _ => EventKind::ALL
+                .into_iter()
+                .find(|kind| kind.name() == name)
+                .map(JournalKind::Event),
+        }
+    }
+}
+impl From<EventKind> for JournalKind {
+    fn from(kind: EventKind) -> Self {
+        JournalKind::Event(kind)
+    }
+}
+
+/// A change on the timer along with the times it was at when it happened
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JournalEntry {
+    pub kind: JournalKind,
+    /// Seconds on real and game time, negative while counting down the start offset
+    pub time: f64,
+    pub game_time: f64,
+    pub at: SystemTime,
+}
+impl JournalEntry {
+    /// Entry for `kind` having just happened on `timer`
+    pub fn new<C: Clock + Clone>(kind: JournalKind, timer: &Timer<C>) -> Self {
+        Self {
+            kind,
+            time: timer
+                .stopwatch_for(TimingMethod::RealTime)
+                .timestamp()
+                .seconds(),
+            game_time: timer
+                .stopwatch_for(TimingMethod::GameTime)
+                .timestamp()
+                .seconds(),
+            // A start goes by the time the attempt gets saved with, to match the two up
+            at: match kind {
+                JournalKind::Event(EventKind::Start) => timer.started_at(),
+                _ => None,
+            }
+            .unwrap_or_else(SystemTime::now),
+        }
+    }
+
+    /// A line like `split 62.5 60.25 1709210096.5`, the last number being the unix time
+    fn line(&self) -> String {
+        let at = self
+            .at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        format!("{} {} {} {at}", self.kind.name(), self.time, self.game_time)
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let (Some(kind), Some(time), Some(game_time), Some(at), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return None;
+        };
+        let number = |s: &str| s.parse::<f64>().ok().filter(|n| n.is_finite());
+        let at = Duration::try_from_secs_f64(number(at)?).ok()?;
+        Some(Self {
+            kind: JournalKind::from_name(kind)?,
+            time: number(time)?,
+            game_time: number(game_time)?,
+            at: SystemTime::UNIX_EPOCH + at,
+        })
+    }
+}
+
+/// Whether the entries are of a run that was left in progress, i.e. they start a run
+/// that never reached a reset or its end
+pub fn is_unfinished(entries: &[JournalEntry]) -> bool {
+    let starts = entries
+        .first()
+        .is_some_and(|entry| entry.kind == JournalKind::Event(EventKind::Start));
+    let last_event = entries.iter().rev().find_map(|entry| match entry.kind {
+        JournalKind::Event(kind) => Some(kind),
+        _ => None,
+    });
+    starts && !matches!(last_event, Some(EventKind::Finish | EventKind::Reset))
+}
+
+/// Whether the run the entries start is the last attempt of `run_data` already, like
+/// when the timer went down between saving the attempt and journaling its end
+pub fn is_recorded(entries: &[JournalEntry], run_data: &RunData) -> bool {
+    let Some(start) = entries.first() else {
+        return false;
+    };
+    let started_at = run_data
+        .attempts()
+        .last()
+        .and_then(|attempt| attempt.started_at());
+    // The journal only keeps the start down to a fraction of a millisecond
+    started_at.is_some_and(|started_at| {
+        let apart = match started_at.duration_since(start.at) {
+            Ok(apart) => apart,
+            Err(err) => err.duration(),
+        };
+        apart < Duration::from_millis(1)
+    })
+}
+
+/// Plays an unfinished run back on `timer`, which has to hold the run it was recorded
+/// on. The timer is left as it was on the last entry, with the time since then added on
+/// when it was running as the run kept going while the timer was down. Returns whether
+/// there was anything to play back.
+pub fn replay<C: Clock + Clone>(timer: &mut Timer<C>, entries: &[JournalEntry]) -> bool {
+    if !is_unfinished(entries) {
+        return false;
+    }
+    let times = |entry: &JournalEntry| {
+        (
+            Timestamp::from_secs_f64(entry.time),
+            Timestamp::from_secs_f64(entry.game_time),
+        )
+    };
+    let mut paused_duration = Duration::ZERO;
+    let mut paused_at = None;
+    for entry in entries {
+        let (time, game_time) = times(entry);
+        if entry.kind == JournalKind::Event(EventKind::Start) {
+            timer.start();
+        }
+        timer.set_times(time, game_time);
+        match entry.kind {
+            JournalKind::Event(EventKind::Start) => {}
+            JournalKind::Event(EventKind::Split | EventKind::Finish) => {
+                timer.split();
+            }
+            JournalKind::Event(EventKind::Skip) => {
+                timer.skip_split();
+            }
+            JournalKind::Event(EventKind::Undo) => {
+                timer.undo_split();
+            }
+            JournalKind::Event(EventKind::Pause) => {
+                timer.toggle_pause();
+                paused_at = Some(entry.at);
+            }
+            JournalKind::Event(EventKind::Resume) => {
+                timer.toggle_pause();
+                if let Some(paused_at) = paused_at.take() {
+                    paused_duration += entry.at.duration_since(paused_at).unwrap_or_default();
+                }
+            }
+            JournalKind::Event(EventKind::Reset) => {
+                timer.reset();
+            }
+            JournalKind::GameTimePause | JournalKind::GameTimeResume => {
+                let pause = entry.kind == JournalKind::GameTimePause;
+                if timer.is_game_time_paused() != pause {
+                    timer.toggle_game_time_pause();
+                }
+            }
+        }
+    }
+
+    let now = SystemTime::now();
+    if let Some(paused_at) = paused_at {
+        paused_duration += now.duration_since(paused_at).unwrap_or_default();
+    }
+    timer.restore_run_times(entries[0].at, paused_duration);
+    let last = entries.last().unwrap();
+    if timer.is_running() {
+        let since = now
+            .duration_since(last.at)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let game_running = timer.stopwatch_for(TimingMethod::GameTime).is_running();
+        let game_since = if game_running { since } else { 0.0 };
+        timer.set_times(
+            Timestamp::from_secs_f64(last.time + since),
+            Timestamp::from_secs_f64(last.game_time + game_since),
+        );
+    }
+    true
+}
+
+/// Append-only log of the run in progress, kept next to its splits file. Every entry is
+/// synced to disk as it's written so the run can be brought back after a crash.
+pub struct Journal {
+    path: PathBuf,
+    file: Option<File>,
+}
+impl Journal {
+    /// Journal of the splits file at `run_path`, like `run.bss.journal`
+    pub fn for_run(run_path: &Path) -> Self {
+        let mut path = run_path.as_os_str().to_owned();
+        path.push(".journal");
+        Self {
+            path: PathBuf::from(path),
+            file: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Entries left by a run that never ended, like when the timer crashed mid-run.
+    /// Lines that can't be read, like one cut short by the crash, are left out.
+    pub fn unfinished(&self) -> std::io::Result<Option<Vec<JournalEntry>>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let mut lines = content.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Not a journal file",
+            ));
+        }
+        let entries: Vec<JournalEntry> = lines.filter_map(JournalEntry::parse).collect();
+        Ok(is_unfinished(&entries).then_some(entries))
+    }
+
+    /// Like `unfinished`, except that a run already recorded as the last attempt of
+    /// `run_data` is left out and the journal cleared, as there's nothing to restore
+    pub fn unfinished_of(
+        &mut self,
+        run_data: &RunData,
+    ) -> std::io::Result<Option<Vec<JournalEntry>>> {
+        match self.unfinished()? {
+            Some(entries) if is_recorded(&entries, run_data) => {
+                self.clear()?;
+                Ok(None)
+            }
+            entries => Ok(entries),
+        }
+    }
+
+    /// Writes down that `kind` just happened on `timer`. A start begins the journal
+    /// anew, anything else goes after what's there. Entries of a run whose start isn't
+    /// in the journal are left out.
+    pub fn record<C: Clock + Clone>(
+        &mut self,
+        kind: JournalKind,
+        timer: &Timer<C>,
+    ) -> std::io::Result<()> {
+        if kind == JournalKind::Event(EventKind::Start) {
+            let mut file = File::create(&self.path)?;
+            writeln!(file, "{HEADER}")?;
+            self.file = Some(file);
+        } else if self.file.is_none() {
+            if !self.path.exists() {
+                return Ok(());
+            }
+            let mut file = OpenOptions::new().append(true).open(&self.path)?;
+            // Whatever was being written when the timer went down gets a line of its own
+            let content = std::fs::read(&self.path)?;
+            if content.last().is_some_and(|b| *b != b'\n') {
+                writeln!(file)?;
+            }
+            self.file = Some(file);
+        }
+        let file = self.file.as_mut().unwrap();
+        writeln!(file, "{}", JournalEntry::new(kind, timer).line())?;
+        file.sync_data()
+    }
+
+    /// Removes the journal, for when the run in it is dealt with
+    pub fn clear(&mut self) -> std::io::Result<()> {
+        self.file = None;
+        match std::fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stopwatch::ManualClock;
+    use crate::timer::tests::three_splits;
+    use crate::timer::TimerPhase;
+
+    #[test]
+    fn entry_lines() {
+        let entry = JournalEntry {
+            kind: JournalKind::Event(EventKind::Split),
+            time: 62.5,
+            game_time: -0.25,
+            at: SystemTime::UNIX_EPOCH + Duration::from_millis(1_709_210_096_500),
+        };
+        assert_eq!("split 62.5 -0.25 1709210096.5", entry.line());
+        assert_eq!(Some(entry), JournalEntry::parse(&entry.line()));
+        assert_eq!(
+            Some(JournalKind::GameTimePause),
+            JournalEntry::parse("game_time_pause 1 1 1").map(|e| e.kind)
+        );
+        assert_eq!(None, JournalEntry::parse("split 62.5 60.2"));
+        assert_eq!(None, JournalEntry::parse("jump 1 1 1"));
+        assert_eq!(None, JournalEntry::parse("split NaN 1 1"));
+    }
+
+    #[test]
+    fn unfinished_runs() {
+        let clock = ManualClock::new();
+        let timer = three_splits(clock.clone());
+        let entry = |kind: EventKind| JournalEntry::new(kind.into(), &timer);
+        let pause = JournalEntry::new(JournalKind::GameTimePause, &timer);
+        let start = entry(EventKind::Start);
+        assert!(!is_unfinished(&[]));
+        assert!(is_unfinished(&[start]));
+        assert!(is_unfinished(&[start, entry(EventKind::Split), pause]));
+        assert!(!is_unfinished(&[start, entry(EventKind::Reset)]));
+        assert!(!is_unfinished(&[start, entry(EventKind::Finish), pause]));
+        assert!(is_unfinished(&[
+            start,
+            entry(EventKind::Finish),
+            entry(EventKind::Undo)
+        ]));
+        assert!(!is_unfinished(&[entry(EventKind::Split)]));
+    }
+
+    fn record(entries: &mut Vec<JournalEntry>, timer: &Timer<ManualClock>, kind: JournalKind) {
+        entries.push(JournalEntry::new(kind, timer));
+    }
+
+    #[test]
+    fn replay_run() {
+        let clock = ManualClock::new();
+        let secs = Duration::from_secs;
+        let mut timer = three_splits(clock.clone());
+        let mut entries = Vec::new();
+        timer.start();
+        record(&mut entries, &timer, EventKind::Start.into());
+        clock.advance(secs(10));
+        timer.split();
+        record(&mut entries, &timer, EventKind::Split.into());
+        clock.advance(secs(5));
+        timer.toggle_game_time_pause();
+        record(&mut entries, &timer, JournalKind::GameTimePause);
+        clock.advance(secs(3));
+        timer.skip_split();
+        record(&mut entries, &timer, EventKind::Skip.into());
+        clock.advance(secs(2));
+        timer.toggle_pause();
+        record(&mut entries, &timer, EventKind::Pause.into());
+
+        let mut restored = three_splits(clock.clone());
+        assert!(replay(&mut restored, &entries));
+        assert_eq!(TimerPhase::Paused, restored.phase());
+        assert_eq!(2, restored.current_split());
+        assert!(restored.is_game_time_paused());
+        for method in [TimingMethod::RealTime, TimingMethod::GameTime] {
+            let sw = timer.stopwatch_for(method);
+            let restored_sw = restored.stopwatch_for(method);
+            assert_eq!(sw.time_elapsed(), restored_sw.time_elapsed());
+            for (split, restored_split) in timer.splits().iter().zip(restored.splits()) {
+                let (split, restored_split) = match method {
+                    TimingMethod::RealTime => (&split.1, &restored_split.1),
+                    TimingMethod::GameTime => (&split.2, &restored_split.2),
+                };
+                assert_eq!(split.is_done(), restored_split.is_done());
+                assert_eq!(split.is_skipped(), restored_split.is_skipped());
+                assert_eq!(
+                    split.time_elapsed(sw),
+                    restored_split.time_elapsed(restored_sw)
+                );
+            }
+        }
+
+        // The restored run goes on like the original
+        assert_eq!(Some(EventKind::Resume), restored.toggle_pause());
+        assert_eq!(Some(EventKind::Finish), restored.split());
+        assert_eq!(1, restored.run_data().attempts().len());
+
+        let finished = [
+            entries.as_slice(),
+            &[JournalEntry::new(EventKind::Finish.into(), &restored)],
+        ]
+        .concat();
+        assert!(!replay(&mut three_splits(clock.clone()), &finished));
+    }
+
+    #[test]
+    fn replay_running_catches_up() {
+        let clock = ManualClock::new();
+        let mut timer = three_splits(clock.clone());
+        timer.start();
+        clock.advance(Duration::from_secs(4));
+        let mut entry = JournalEntry::new(EventKind::Start.into(), &timer);
+        entry.time = 0.0;
+        entry.game_time = 0.0;
+        entry.at -= Duration::from_secs(20);
+
+        let mut restored = three_splits(clock.clone());
+        assert!(replay(&mut restored, &[entry]));
+        assert!(restored.is_running());
+        let elapsed = restored
+            .stopwatch_for(TimingMethod::RealTime)
+            .time_elapsed();
+        assert!(elapsed >= Duration::from_secs(20) && elapsed < Duration::from_secs(21));
+    }
+
+    #[test]
+    fn journal_file() {
+        let dir = std::env::temp_dir().join(format!("haidomo-journal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut journal = Journal::for_run(&dir.join("run.bss"));
+        assert_eq!(dir.join("run.bss.journal"), journal.path());
+        assert!(journal.unfinished().unwrap().is_none());
+
+        let clock = ManualClock::new();
+        let mut timer = three_splits(clock.clone());
+        // Nothing to go after without a start
+        journal.record(EventKind::Split.into(), &timer).unwrap();
+        assert!(!journal.path().exists());
+
+        timer.start();
+        journal.record(EventKind::Start.into(), &timer).unwrap();
+        clock.advance(Duration::from_secs(3));
+        timer.split();
+        journal.record(EventKind::Split.into(), &timer).unwrap();
+        let entries = journal.unfinished().unwrap().unwrap();
+        assert_eq!(2, entries.len());
+        assert_eq!(3.0, entries[1].time);
+
+        // Lines cut short by a crash are left out, and a new journal goes on after them
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(journal.path())
+            .unwrap();
+        write!(file, "split 5.").unwrap();
+        assert_eq!(2, journal.unfinished().unwrap().unwrap().len());
+        let mut journal = Journal::for_run(&dir.join("run.bss"));
+        timer.toggle_pause();
+        journal.record(EventKind::Pause.into(), &timer).unwrap();
+        let entries = journal.unfinished().unwrap().unwrap();
+        assert_eq!(3, entries.len());
+        assert_eq!(JournalKind::Event(EventKind::Pause), entries[2].kind);
+        timer.reset();
+        journal.record(EventKind::Reset.into(), &timer).unwrap();
+        assert!(journal.unfinished().unwrap().is_none());
+
+        journal.clear().unwrap();
+        assert!(!journal.path().exists());
+        journal.clear().unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn recorded_runs_are_not_restored() {
+        let dir = std::env::temp_dir().join(format!("haidomo-recorded-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut journal = Journal::for_run(&dir.join("run.bss"));
+        let clock = ManualClock::new();
+        let mut timer = three_splits(clock.clone());
+
+        // Finished and saved, but the timer went down before journaling the finish
+        timer.start();
+        journal.record(EventKind::Start.into(), &timer).unwrap();
+        for _ in 0..2 {
+            clock.advance(Duration::from_secs(3));
+            timer.split();
+            journal.record(EventKind::Split.into(), &timer).unwrap();
+        }
+        let entries = journal.unfinished().unwrap().unwrap();
+        assert!(!is_recorded(&entries, timer.run_data()));
+        assert_eq!(
+            entries,
+            journal.unfinished_of(timer.run_data()).unwrap().unwrap()
+        );
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(Some(EventKind::Finish), timer.split());
+        assert_eq!(1, timer.run_data().attempts().len());
+        // Read back from the file like after a restart
+        let entries = journal.unfinished().unwrap().unwrap();
+        assert!(is_recorded(&entries, timer.run_data()));
+        assert_eq!(None, journal.unfinished_of(timer.run_data()).unwrap());
+        assert!(!journal.path().exists());
+
+        // Same for a reset
+        timer.start();
+        journal.record(EventKind::Start.into(), &timer).unwrap();
+        clock.advance(Duration::from_secs(3));
+        timer.split();
+        journal.record(EventKind::Split.into(), &timer).unwrap();
+        assert_eq!(Some(EventKind::Reset), timer.reset());
+        assert_eq!(2, timer.run_data().attempts().len());
+        assert_eq!(None, journal.unfinished_of(timer.run_data()).unwrap());
+
+        // A run started after the last attempt isn't it
+        let mut later = entries.clone();
+        later[0].at += Duration::from_secs(1);
+        assert!(!is_recorded(&later, timer.run_data()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,10 @@
+//! Timing, splits and run history of Hai Domo! without any frontend attached
+
+pub mod action;
+pub mod journal;
+pub mod livesplit;
+pub mod run_file;
+pub mod settings;
+pub mod splits_file;
+pub mod stopwatch;
+pub mod timer;
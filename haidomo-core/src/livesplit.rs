@@ -0,0 +1,1010 @@
+use crate::splits_file::{AttemptData, AttemptOutcome, RunData, RunMetadata};
+use crate::stopwatch::TimingMethod;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+const PERSONAL_BEST: &str = "Personal Best";
+const LSS_VERSION: &str = "1.7.0";
+
+#[derive(Debug)]
+pub enum LssError {
+    Xml(quick_xml::Error),
+    MissingElement(&'static str),
+    InvalidTime(String),
+    InvalidAttemptId(String),
+}
+impl From<quick_xml::Error> for LssError {
+    fn from(err: quick_xml::Error) -> Self {
+        LssError::Xml(err)
+    }
+}
+impl std::fmt::Display for LssError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LssError::Xml(err) => write!(f, "The LiveSplit file isn't valid XML: {err}"),
+            LssError::MissingElement(name) => {
+                write!(f, "The LiveSplit file is missing its <{name}> element")
+            }
+            LssError::InvalidTime(time) => write!(f, "Invalid time `{time}` in the LiveSplit file"),
+            LssError::InvalidAttemptId(id) => {
+                write!(f, "Invalid attempt id `{id}` in the LiveSplit file")
+            }
+        }
+    }
+}
+impl std::error::Error for LssError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LssError::Xml(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Times {
+    real: Option<Duration>,
+    game: Option<Duration>,
+}
+
+/// Bare bones DOM, LiveSplit files are small enough to hold in memory whole
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+impl Element {
+    fn from_start(start: &BytesStart) -> Result<Self, LssError> {
+        let mut attributes = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr.map_err(quick_xml::Error::from)?;
+            let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+            let value = attr.unescape_value()?.into_owned();
+            attributes.push((key, value));
+        }
+        Ok(Self {
+            name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+            attributes,
+            children: Vec::new(),
+            text: String::new(),
+        })
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|c| c.text.trim())
+    }
+
+    /// Reads the `RealTime` and `GameTime` of a time element, either is `None` when
+    /// there's no time recorded for it
+    fn times(&self) -> Result<Times, LssError> {
+        let time = |name: &str| match self.child_text(name) {
+            None | Some("") => Ok(None),
+            Some(text) => parse_time(text).map(Some),
+        };
+        Ok(Times {
+            real: time("RealTime")?,
+            game: time("GameTime")?,
+        })
+    }
+}
+
+fn parse_document(content: &str) -> Result<Element, LssError> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<Element> = vec![Element::default()];
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => stack.push(Element::from_start(&start)?),
+            Event::Empty(start) => {
+                let element = Element::from_start(&start)?;
+                stack.last_mut().unwrap().children.push(element);
+            }
+            Event::End(_) => {
+                if stack.len() < 2 {
+                    return Err(LssError::MissingElement("Run"));
+                }
+                let element = stack.pop().unwrap();
+                stack.last_mut().unwrap().children.push(element);
+            }
+            Event::Text(text) => stack.last_mut().unwrap().text.push_str(&text.unescape()?),
+            Event::Eof => break,
+            // Icons come as CDATA and there's nothing else of interest
+            _ => {}
+        }
+    }
+    let document = stack.swap_remove(0);
+    document
+        .children
+        .into_iter()
+        .find(|c| c.name == "Run")
+        .ok_or(LssError::MissingElement("Run"))
+}
+
+/// Parses a .NET `TimeSpan` as LiveSplit writes them: `[d.]hh:mm:ss[.fffffff]`
+fn parse_time(text: &str) -> Result<Duration, LssError> {
+    let invalid = || LssError::InvalidTime(text.to_string());
+    let mut parts = text.split(':');
+    let (Some(hours), Some(minutes), Some(seconds), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let (days, hours) = match hours.split_once('.') {
+        Some((days, hours)) => (days, hours),
+        None => ("0", hours),
+    };
+    let (seconds, fraction) = match seconds.split_once('.') {
+        Some((seconds, fraction)) => (seconds, fraction),
+        None => (seconds, ""),
+    };
+    let number = |s: &str| s.parse::<u64>().map_err(|_| invalid());
+    let days = number(days)?;
+    let hours = number(hours)?;
+    let minutes = number(minutes)?;
+    let seconds = number(seconds)?;
+    if minutes >= 60 || seconds >= 60 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let mut nanos = 0u32;
+    for i in 0..9 {
+        let digit = fraction.as_bytes().get(i).map_or(0, |b| b - b'0');
+        nanos = nanos * 10 + digit as u32;
+    }
+    let total_secs = days
+        .checked_mul(24)
+        .and_then(|hours_in_days| hours_in_days.checked_add(hours))
+        .and_then(|hours| hours.checked_mul(60))
+        .and_then(|minutes_in_hours| minutes_in_hours.checked_add(minutes))
+        .and_then(|minutes| minutes.checked_mul(60))
+        .and_then(|seconds_in_minutes| seconds_in_minutes.checked_add(seconds))
+        .ok_or_else(invalid)?;
+    Ok(Duration::new(total_secs, nanos))
+}
+
+pub fn format_time(time: Duration) -> String {
+    let total_secs = time.as_secs();
+    // TimeSpan has a resolution of 100ns ticks
+    let ticks = time.subsec_nanos() / 100;
+    let days = total_secs / 86_400;
+    let hours = (total_secs / 3600) % 24;
+    let mins = (total_secs / 60) % 60;
+    let secs = total_secs % 60;
+    if days > 0 {
+        format!("{days}.{hours:02}:{mins:02}:{secs:02}.{ticks:07}")
+    } else {
+        format!("{hours:02}:{mins:02}:{secs:02}.{ticks:07}")
+    }
+}
+
+/// Parses a `TimeSpan` that can be negative, like the run offset, into seconds
+fn parse_signed_time(text: &str) -> Result<f64, LssError> {
+    match text.strip_prefix('-') {
+        Some(time) => Ok(-parse_time(time)?.as_secs_f64()),
+        None => Ok(parse_time(text)?.as_secs_f64()),
+    }
+}
+
+/// Formats seconds that can be negative as a `TimeSpan`, like `-00:00:01.5000000`
+pub fn format_signed_time(secs: f64) -> String {
+    let time = format_time(Duration::try_from_secs_f64(secs.abs()).unwrap_or_default());
+    if secs < 0.0 {
+        format!("-{time}")
+    } else {
+        time
+    }
+}
+
+/// Days since the unix epoch of a date on the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Year, month and day of a count of days since the unix epoch
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Parses the UTC timestamps of attempts: `MM/dd/yyyy HH:mm:ss`
+fn parse_date_time(text: &str) -> Option<SystemTime> {
+    let (date, time) = text.trim().split_once(' ')?;
+    let number = |s: &str| s.parse::<i64>().ok();
+    let mut date = date.split('/').map(number);
+    let (Some(month), Some(day), Some(year), None) =
+        (date.next()?, date.next()?, date.next()?, date.next())
+    else {
+        return None;
+    };
+    let mut time = time.split(':').map(number);
+    let (Some(hours), Some(minutes), Some(seconds), None) =
+        (time.next()?, time.next()?, time.next()?, time.next())
+    else {
+        return None;
+    };
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hours >= 24
+        || minutes >= 60
+        || seconds >= 60
+    {
+        return None;
+    }
+    let secs = days_from_civil(year, month, day) * 86_400 + (hours * 60 + minutes) * 60 + seconds;
+    let secs = u64::try_from(secs).ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+fn format_date_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let secs = secs.rem_euclid(86_400);
+    format!(
+        "{month:02}/{day:02}/{year:04} {:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds a run out of the contents of a LiveSplit .lss file.
+///
+/// Every entry of the attempt history becomes an attempt holding the segment times
+/// recorded under its id. Entries with a time of their own are the finished attempts and
+/// keep that time as their total. If the personal best isn't backed by any of those, it's added
+/// as one more attempt so it isn't lost.
+pub fn read_lss(content: &str) -> Result<RunData, LssError> {
+    let run = parse_document(content)?;
+    let metadata = read_metadata(&run);
+
+    let segments: Vec<&Element> = run
+        .child("Segments")
+        .ok_or(LssError::MissingElement("Segments"))?
+        .children_named("Segment")
+        .collect();
+    let segment_names = segments
+        .iter()
+        .map(|s| s.child_text("Name").unwrap_or_default().to_string())
+        .collect();
+    let (split_names, groups) = read_subsplits(segment_names);
+    let mut run_data = RunData::new(metadata.game.clone(), split_names);
+    *run_data.metadata_mut() = metadata;
+    for (name, start, len) in groups {
+        // Groups come out in order and apart from each other
+        let _ = run_data.add_group(name, start, len);
+    }
+    if let Some(offset) = run.child_text("Offset") {
+        run_data.set_start_offset(parse_signed_time(offset)?);
+    }
+
+    // Segment history of every segment, keyed by attempt id
+    let mut histories: Vec<HashMap<i64, Times>> = Vec::with_capacity(segments.len());
+    for segment in segments.iter() {
+        let mut history = HashMap::new();
+        if let Some(segment_history) = segment.child("SegmentHistory") {
+            for time in segment_history.children_named("Time") {
+                // The first time wins if an id shows up twice
+                history
+                    .entry(parse_attempt_id(time)?)
+                    .or_insert(time.times()?);
+            }
+        }
+        histories.push(history);
+    }
+
+    for (s, segment) in segments.iter().enumerate() {
+        if let Some(best_segment) = segment.child("BestSegmentTime") {
+            let best = best_segment.times()?;
+            if let Some(real) = best.real {
+                run_data.improve_best_segment(s, TimingMethod::RealTime, real.as_secs_f64());
+            }
+            if let Some(game) = best.game {
+                run_data.improve_best_segment(s, TimingMethod::GameTime, game.as_secs_f64());
+            }
+        }
+    }
+
+    let mut completed_totals = Vec::new();
+    if let Some(attempt_history) = run.child("AttemptHistory") {
+        for attempt in attempt_history.children_named("Attempt") {
+            let id = parse_attempt_id(attempt)?;
+            // Segments missing from the history weren't reached, the ones in it without a
+            // time were skipped
+            let reached: Vec<Times> = histories
+                .iter()
+                .map_while(|history| history.get(&id).copied())
+                .collect();
+            let split_durations: Vec<Option<Duration>> = reached.iter().map(|t| t.real).collect();
+            let game_split_durations = if reached.iter().any(|t| t.game.is_some()) {
+                reached.iter().map(|t| t.game).collect()
+            } else {
+                Vec::new()
+            };
+            // Only finished attempts have a time of their own, which holds even when
+            // LiveSplit pruned some of their segment history
+            let attempt_times = attempt.times()?;
+            let attempt_data = if attempt_times.real.is_some() || attempt_times.game.is_some() {
+                let split_durations =
+                    complete_segments(split_durations, attempt_times.real, segments.len());
+                let game_split_durations =
+                    complete_segments(game_split_durations, attempt_times.game, segments.len());
+                let total = attempt_times
+                    .real
+                    .unwrap_or_else(|| split_durations.iter().flatten().sum());
+                let game_total = attempt_times
+                    .game
+                    .unwrap_or_else(|| game_split_durations.iter().flatten().sum());
+                completed_totals.push(total);
+                run_data.add_unfinished_attempt(
+                    AttemptOutcome::Completed,
+                    split_durations,
+                    game_split_durations,
+                    total,
+                    game_total,
+                )
+            } else {
+                let total = split_durations.iter().flatten().sum();
+                let game_total = game_split_durations.iter().flatten().sum();
+                run_data.add_unfinished_attempt(
+                    AttemptOutcome::Reset,
+                    split_durations,
+                    game_split_durations,
+                    total,
+                    game_total,
+                )
+            };
+            let timestamp = |name| attempt.attribute(name).and_then(parse_date_time);
+            attempt_data.set_timestamps(timestamp("started"), timestamp("ended"));
+            // LiveSplit only keeps the time spent paused, not how many times it was
+            if let Some(pause_time) = attempt.child_text("PauseTime") {
+                attempt_data.set_pauses(0, parse_time(pause_time)?);
+            }
+        }
+    }
+
+    let mut pb_splits = Vec::with_capacity(segments.len());
+    for segment in segments.iter() {
+        let pb_times = segment
+            .child("SplitTimes")
+            .and_then(|times| {
+                times
+                    .children_named("SplitTime")
+                    .find(|t| t.attribute("name") == Some(PERSONAL_BEST))
+            })
+            .map(|t| t.times())
+            .transpose()?
+            .unwrap_or_default();
+        pb_splits.push(pb_times);
+    }
+    if let Some(pb_total) = pb_splits.last().and_then(|t| t.real) {
+        let backed = completed_totals
+            .iter()
+            .any(|total| total.abs_diff(pb_total) < Duration::from_millis(1));
+        if !backed {
+            let pb_real: Vec<Option<Duration>> = pb_splits.iter().map(|t| t.real).collect();
+            let pb_game: Vec<Option<Duration>> = match pb_splits.last().and_then(|t| t.game) {
+                Some(_) => pb_splits.iter().map(|t| t.game).collect(),
+                None => Vec::new(),
+            };
+            run_data.add_attempt_with_game_time(
+                segment_durations(&pb_real),
+                segment_durations(&pb_game),
+            );
+        }
+    }
+
+    Ok(run_data)
+}
+
+/// Splits the group information out of the segment names. LiveSplit marks subsplits with
+/// a leading `-`, and the segment closing the group can name it like `{World 1} Boss`.
+/// Returns the bare split names and the name, start and length of every group.
+fn read_subsplits(segment_names: Vec<String>) -> (Vec<String>, Vec<(String, usize, usize)>) {
+    let mut split_names = Vec::with_capacity(segment_names.len());
+    let mut groups = Vec::new();
+    let mut group_start = None;
+    for (i, name) in segment_names.into_iter().enumerate() {
+        if let Some(name) = name.strip_prefix('-') {
+            group_start.get_or_insert(i);
+            split_names.push(name.to_string());
+            continue;
+        }
+        let braced = name
+            .strip_prefix('{')
+            .and_then(|rest| rest.split_once('}'))
+            .map(|(group, rest)| (group.to_string(), rest.trim_start().to_string()));
+        match (group_start.take(), braced) {
+            (start, Some((group, name))) => {
+                let start = start.unwrap_or(i);
+                groups.push((group, start, i + 1 - start));
+                split_names.push(name);
+            }
+            (Some(start), None) => {
+                groups.push((name.clone(), start, i + 1 - start));
+                split_names.push(name);
+            }
+            (None, None) => split_names.push(name),
+        }
+    }
+    // Subsplits left open at the end get grouped under the last one
+    if let (Some(start), Some(last)) = (group_start, split_names.last()) {
+        groups.push((last.clone(), start, split_names.len() - start));
+    }
+    (split_names, groups)
+}
+
+/// Segment name as LiveSplit expects it for subsplits, the inverse of `read_subsplits`
+fn segment_name(run_data: &RunData, index: usize) -> String {
+    let name = &run_data.split_names()[index];
+    match run_data.group_of(index) {
+        Some(group) if group.range().end == index + 1 => format!("{{{}}} {name}", group.name()),
+        Some(_) => format!("-{name}"),
+        None => name.clone(),
+    }
+}
+
+fn read_metadata(run: &Element) -> RunMetadata {
+    let mut game = run.child_text("GameName").unwrap_or_default().to_string();
+    if game.is_empty() {
+        game = String::from("Untitled");
+    }
+    let mut metadata = RunMetadata {
+        game,
+        category: run
+            .child_text("CategoryName")
+            .unwrap_or_default()
+            .to_string(),
+        ..Default::default()
+    };
+    let Some(lss_metadata) = run.child("Metadata") else {
+        return metadata;
+    };
+    if let Some(platform) = lss_metadata.child("Platform") {
+        metadata.platform = platform.text.trim().to_string();
+        metadata.uses_emulator = platform.attribute("usesEmulator") == Some("True");
+    }
+    metadata.region = lss_metadata
+        .child_text("Region")
+        .unwrap_or_default()
+        .to_string();
+    if let Some(variables) = lss_metadata.child("Variables") {
+        for variable in variables.children_named("Variable") {
+            let name = variable.attribute("name").unwrap_or_default();
+            metadata
+                .variables
+                .push((name.to_string(), variable.text.trim().to_string()));
+        }
+    }
+    metadata
+}
+
+/// Fills in the segments of a finished attempt missing from its history. They're left as
+/// skipped with what's left of `total` on the last one, so the segments still add up.
+fn complete_segments(
+    mut segments: Vec<Option<Duration>>,
+    total: Option<Duration>,
+    count: usize,
+) -> Vec<Option<Duration>> {
+    let Some(total) = total.filter(|_| segments.len() < count) else {
+        return segments;
+    };
+    let known: Duration = segments.iter().flatten().sum();
+    segments.resize(count - 1, None);
+    segments.push(Some(total.saturating_sub(known)));
+    segments
+}
+
+/// Turns cumulative split times into the time spent on each segment. Skipped splits have
+/// no time and the next segment takes the time of both.
+fn segment_durations(splits: &[Option<Duration>]) -> Vec<Option<Duration>> {
+    let mut previous = Duration::ZERO;
+    splits
+        .iter()
+        .map(|split| {
+            let split = (*split)?;
+            let segment = split.saturating_sub(previous);
+            previous = split;
+            Some(segment)
+        })
+        .collect()
+}
+
+fn parse_attempt_id(element: &Element) -> Result<i64, LssError> {
+    let id = element.attribute("id").unwrap_or_default();
+    id.parse()
+        .map_err(|_| LssError::InvalidAttemptId(id.to_string()))
+}
+
+/// Appends a time element like `<Time id="1">` holding its real and game times, or an
+/// empty element when neither is known
+fn push_times_element(xml: &mut String, indent: usize, tag: &str, attributes: &str, times: Times) {
+    push_times_element_with(xml, indent, tag, attributes, times, &[]);
+}
+
+/// Like `push_times_element` with extra time elements after the real and game time
+fn push_times_element_with(
+    xml: &mut String,
+    indent: usize,
+    tag: &str,
+    attributes: &str,
+    times: Times,
+    extra: &[(&str, Duration)],
+) {
+    let pad = " ".repeat(indent);
+    if times.real.is_none() && times.game.is_none() && extra.is_empty() {
+        xml.push_str(&format!("{pad}<{tag}{attributes} />\n"));
+        return;
+    }
+    xml.push_str(&format!("{pad}<{tag}{attributes}>\n"));
+    if let Some(real) = times.real {
+        xml.push_str(&format!(
+            "{pad}  <RealTime>{}</RealTime>\n",
+            format_time(real)
+        ));
+    }
+    if let Some(game) = times.game {
+        xml.push_str(&format!(
+            "{pad}  <GameTime>{}</GameTime>\n",
+            format_time(game)
+        ));
+    }
+    for (name, time) in extra {
+        xml.push_str(&format!("{pad}  <{name}>{}</{name}>\n", format_time(*time)));
+    }
+    xml.push_str(&format!("{pad}</{tag}>\n"));
+}
+
+/// Writes a run as a LiveSplit .lss file. Attempt ids are the 1-based position of the
+/// attempt in the run.
+pub fn write_lss(run_data: &RunData) -> String {
+    let metadata = run_data.metadata();
+    let split_names = run_data.split_names();
+    let attempts = run_data.attempts();
+    let is_completed = |attempt: &AttemptData, split_times: &[Option<f64>]| {
+        attempt.outcome() == AttemptOutcome::Completed && split_times.len() == split_names.len()
+    };
+    let pb = run_data.personal_best(TimingMethod::RealTime);
+    let best_segments = run_data.best_segments_for(TimingMethod::RealTime);
+    let game_best_segments = run_data.best_segments_for(TimingMethod::GameTime);
+    let segment_times = |attempt: &AttemptData, s: usize| {
+        let time = |method| {
+            let secs = attempt.split_times_for(method).get(s).copied().flatten()?;
+            Some(Duration::from_secs_f64(secs))
+        };
+        Times {
+            real: time(TimingMethod::RealTime),
+            game: time(TimingMethod::GameTime),
+        }
+    };
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<Run version=\"{LSS_VERSION}\">\n"));
+    xml.push_str("  <GameIcon />\n");
+    xml.push_str(&format!(
+        "  <GameName>{}</GameName>\n",
+        escape(&metadata.game)
+    ));
+    xml.push_str(&format!(
+        "  <CategoryName>{}</CategoryName>\n",
+        escape(&metadata.category)
+    ));
+    xml.push_str("  <Metadata>\n");
+    xml.push_str("    <Run id=\"\" />\n");
+    let uses_emulator = if metadata.uses_emulator {
+        "True"
+    } else {
+        "False"
+    };
+    xml.push_str(&format!(
+        "    <Platform usesEmulator=\"{uses_emulator}\">{}</Platform>\n",
+        escape(&metadata.platform)
+    ));
+    xml.push_str(&format!(
+        "    <Region>{}</Region>\n",
+        escape(&metadata.region)
+    ));
+    xml.push_str("    <Variables>\n");
+    for (name, value) in metadata.variables.iter() {
+        xml.push_str(&format!(
+            "      <Variable name=\"{}\">{}</Variable>\n",
+            escape(name),
+            escape(value)
+        ));
+    }
+    xml.push_str("    </Variables>\n");
+    xml.push_str("  </Metadata>\n");
+    xml.push_str(&format!(
+        "  <Offset>{}</Offset>\n",
+        format_signed_time(run_data.start_offset())
+    ));
+    xml.push_str(&format!(
+        "  <AttemptCount>{}</AttemptCount>\n",
+        attempts.len()
+    ));
+
+    xml.push_str("  <AttemptHistory>\n");
+    for (i, attempt) in attempts.iter().enumerate() {
+        let times = Times {
+            real: is_completed(attempt, attempt.split_times()).then(|| attempt.total_duration()),
+            game: is_completed(attempt, attempt.game_split_times())
+                .then(|| attempt.game_total_duration()),
+        };
+        let mut attributes = format!(" id=\"{}\"", i + 1);
+        if let Some(started_at) = attempt.started_at() {
+            let started_at = format_date_time(started_at);
+            attributes.push_str(&format!(
+                " started=\"{started_at}\" isStartedSynced=\"False\""
+            ));
+        }
+        if let Some(ended_at) = attempt.ended_at() {
+            let ended_at = format_date_time(ended_at);
+            attributes.push_str(&format!(" ended=\"{ended_at}\" isEndedSynced=\"False\""));
+        }
+        let paused = attempt.paused_duration();
+        let extra: &[(&str, Duration)] = if paused.is_zero() {
+            &[]
+        } else {
+            &[("PauseTime", paused)]
+        };
+        push_times_element_with(&mut xml, 4, "Attempt", &attributes, times, extra);
+    }
+    xml.push_str("  </AttemptHistory>\n");
+
+    xml.push_str("  <Segments>\n");
+    let mut pb_total = Times::default();
+    for s in 0..split_names.len() {
+        xml.push_str("    <Segment>\n");
+        xml.push_str(&format!(
+            "      <Name>{}</Name>\n",
+            escape(&segment_name(run_data, s))
+        ));
+        xml.push_str("      <Icon />\n");
+
+        xml.push_str("      <SplitTimes>\n");
+        let mut pb_split = Times::default();
+        if let Some(pb) = pb {
+            let segment = segment_times(pb, s);
+            // Skipped splits have no split time but the time still adds up
+            if let Some(real) = segment.real {
+                let total = pb_total.real.unwrap_or_default() + real;
+                pb_total.real = Some(total);
+                pb_split.real = Some(total);
+            }
+            if let Some(game) = segment.game {
+                let total = pb_total.game.unwrap_or_default() + game;
+                pb_total.game = Some(total);
+                pb_split.game = Some(total);
+            }
+        }
+        let attributes = format!(" name=\"{PERSONAL_BEST}\"");
+        push_times_element(&mut xml, 8, "SplitTime", &attributes, pb_split);
+        xml.push_str("      </SplitTimes>\n");
+
+        let best_segment = Times {
+            real: best_segments[s].map(Duration::from_secs_f64),
+            game: game_best_segments[s].map(Duration::from_secs_f64),
+        };
+        push_times_element(&mut xml, 6, "BestSegmentTime", "", best_segment);
+
+        xml.push_str("      <SegmentHistory>\n");
+        for (i, attempt) in attempts.iter().enumerate() {
+            // Skipped splits go in the history without a time
+            if s < attempt.split_times().len() {
+                let times = segment_times(attempt, s);
+                push_times_element(&mut xml, 8, "Time", &format!(" id=\"{}\"", i + 1), times);
+            }
+        }
+        xml.push_str("      </SegmentHistory>\n");
+        xml.push_str("    </Segment>\n");
+    }
+    xml.push_str("  </Segments>\n");
+    xml.push_str("  <AutoSplitterSettings />\n");
+    xml.push_str("</Run>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_LSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Run version="1.7.0">
+  <GameIcon />
+  <GameName>Super Mario 64</GameName>
+  <CategoryName>16 Star</CategoryName>
+  <Metadata>
+    <Run id="" />
+    <Platform usesEmulator="True">Nintendo 64</Platform>
+    <Region>USA</Region>
+    <Variables>
+      <Variable name="Version">US 1.0</Variable>
+    </Variables>
+  </Metadata>
+  <Offset>-00:00:01.5000000</Offset>
+  <AttemptCount>3</AttemptCount>
+  <AttemptHistory>
+    <Attempt id="1" started="01/02/2024 10:00:00" isStartedSynced="True" ended="01/02/2024 10:01:00" isEndedSynced="True">
+      <RealTime>00:00:30.5000000</RealTime>
+    </Attempt>
+    <Attempt id="2" started="01/02/2024 10:05:00" isStartedSynced="True" ended="01/02/2024 10:05:12" isEndedSynced="True" />
+    <Attempt id="3" started="01/02/2024 10:10:00" isStartedSynced="True" ended="01/02/2024 10:11:00" isEndedSynced="True">
+      <RealTime>00:00:28.0000000</RealTime>
+      <PauseTime>00:00:12.5000000</PauseTime>
+    </Attempt>
+  </AttemptHistory>
+  <Segments>
+    <Segment>
+      <Name>Bob-omb &amp; Friends</Name>
+      <Icon><![CDATA[AAEAAAD/////]]></Icon>
+      <SplitTimes>
+        <SplitTime name="Personal Best">
+          <RealTime>00:00:12.0000000</RealTime>
+        </SplitTime>
+      </SplitTimes>
+      <BestSegmentTime>
+        <RealTime>00:00:10.2500000</RealTime>
+      </BestSegmentTime>
+      <SegmentHistory>
+        <Time id="1">
+          <RealTime>00:00:10.2500000</RealTime>
+        </Time>
+        <Time id="2">
+          <RealTime>00:00:11.0000000</RealTime>
+        </Time>
+        <Time id="3">
+          <RealTime>00:00:12.0000000</RealTime>
+          <GameTime>00:00:11.5000000</GameTime>
+        </Time>
+      </SegmentHistory>
+    </Segment>
+    <Segment>
+      <Name>Bowser</Name>
+      <Icon />
+      <SplitTimes>
+        <SplitTime name="Personal Best">
+          <RealTime>00:00:28.0000000</RealTime>
+        </SplitTime>
+      </SplitTimes>
+      <BestSegmentTime>
+        <RealTime>00:00:16.0000000</RealTime>
+      </BestSegmentTime>
+      <SegmentHistory>
+        <Time id="1">
+          <RealTime>00:00:20.2500000</RealTime>
+        </Time>
+        <Time id="3">
+          <RealTime>00:00:16.0000000</RealTime>
+          <GameTime>00:00:15.0000000</GameTime>
+        </Time>
+      </SegmentHistory>
+    </Segment>
+  </Segments>
+  <AutoSplitterSettings />
+</Run>
+"#;
+
+    #[test]
+    fn import_lss() {
+        let run = read_lss(SAMPLE_LSS).expect("Expected to be able to parse the sample .lss");
+        let metadata = run.metadata();
+        assert_eq!("Super Mario 64", metadata.game);
+        assert_eq!("16 Star", metadata.category);
+        assert_eq!("Nintendo 64", metadata.platform);
+        assert_eq!("USA", metadata.region);
+        assert!(metadata.uses_emulator);
+        assert_eq!(
+            vec![("Version".to_string(), "US 1.0".to_string())],
+            metadata.variables
+        );
+        assert_eq!(
+            &["Bob-omb & Friends".to_string(), "Bowser".to_string()],
+            run.split_names()
+        );
+        assert_eq!(-1.5, run.start_offset());
+
+        let attempts = run.attempts();
+        assert_eq!(3, attempts.len(), "PB is backed by attempt 3 already");
+        assert_eq!(&[Some(10.25), Some(20.25)], attempts[0].split_times());
+        assert_eq!(&[Some(11.0)], attempts[1].split_times());
+        assert_eq!(&[Some(12.0), Some(16.0)], attempts[2].split_times());
+        assert_eq!(Duration::from_secs(28), attempts[2].total_duration());
+        assert_eq!(&[Some(11.5), Some(15.0)], attempts[2].game_split_times());
+        assert!(attempts[0].game_split_times().is_empty());
+        assert_eq!(AttemptOutcome::Completed, attempts[0].outcome());
+        assert_eq!(AttemptOutcome::Reset, attempts[1].outcome());
+        assert_eq!(
+            Some("01/02/2024 10:05:12".to_string()),
+            attempts[1].ended_at().map(format_date_time)
+        );
+        assert_eq!(Duration::from_millis(12_500), attempts[2].paused_duration());
+        assert_eq!(
+            vec![Some(10.25), Some(16.0)],
+            run.best_segments_for(TimingMethod::RealTime)
+        );
+    }
+
+    #[test]
+    fn import_pb_without_history() {
+        // Attempt 3 becomes a reset with no history and the PB is left on its own
+        let content = SAMPLE_LSS
+            .replace("<Time id=\"3\">", "<Time id=\"9\">")
+            .replace("<Attempt id=\"3\"", "<Attempt id=\"4\"")
+            .replace(
+                "<RealTime>00:00:28.0000000</RealTime>\n      <PauseTime>",
+                "<PauseTime>",
+            );
+        let run = read_lss(&content).expect("Expected to be able to parse the sample .lss");
+        let attempts = run.attempts();
+        assert_eq!(4, attempts.len(), "PB should be added as its own attempt");
+        assert!(attempts[2].split_times().is_empty());
+        assert_eq!(&[Some(12.0), Some(16.0)], attempts[3].split_times());
+    }
+
+    #[test]
+    fn import_pruned_history() {
+        // Attempt 1 finished but its time on the last segment is gone
+        let content = SAMPLE_LSS.replacen(
+            "<Time id=\"1\">\n          <RealTime>00:00:20.2500000</RealTime>\n        </Time>",
+            "",
+            1,
+        );
+        assert_ne!(SAMPLE_LSS, content);
+        let run = read_lss(&content).expect("Expected to be able to parse the sample .lss");
+        let attempt = &run.attempts()[0];
+        assert_eq!(AttemptOutcome::Completed, attempt.outcome());
+        assert_eq!(Duration::from_millis(30_500), attempt.total_duration());
+        assert_eq!(&[Some(10.25), Some(20.25)], attempt.split_times());
+
+        // Without the first segment either the whole time goes on the last one
+        let content = content.replacen(
+            "<Time id=\"1\">\n          <RealTime>00:00:10.2500000</RealTime>\n        </Time>",
+            "",
+            1,
+        );
+        let run = read_lss(&content).expect("Expected to be able to parse the sample .lss");
+        let attempt = &run.attempts()[0];
+        assert_eq!(AttemptOutcome::Completed, attempt.outcome());
+        assert_eq!(&[None, Some(30.5)], attempt.split_times());
+        assert_eq!(3, run.attempts().len(), "PB is backed by attempt 3 already");
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let exp_run = read_lss(SAMPLE_LSS).expect("Expected to be able to parse the sample .lss");
+        let content = write_lss(&exp_run);
+        let got_run = read_lss(&content).expect("Expected to be able to parse exported .lss");
+
+        assert_eq!(exp_run.metadata(), got_run.metadata());
+        assert_eq!(exp_run.split_names(), got_run.split_names());
+        assert_eq!(exp_run.start_offset(), got_run.start_offset());
+        assert_eq!(exp_run.attempts().len(), got_run.attempts().len());
+        for (exp, got) in exp_run.attempts().iter().zip(got_run.attempts()) {
+            assert_eq!(exp.split_times(), got.split_times());
+            assert_eq!(exp.total_duration(), got.total_duration());
+            assert_eq!(exp.game_split_times(), got.game_split_times());
+            assert_eq!(exp.outcome(), got.outcome());
+            assert_eq!(exp.started_at(), got.started_at());
+            assert_eq!(exp.ended_at(), got.ended_at());
+            assert_eq!(exp.paused_duration(), got.paused_duration());
+        }
+        assert!(content.contains("<RealTime>00:00:28.0000000</RealTime>"));
+    }
+
+    #[test]
+    fn subsplits() {
+        let names = [
+            "-1-1",
+            "-1-2",
+            "{World 1} 1-3",
+            "Bridge",
+            "-2-1",
+            "2-2",
+            "-3-1",
+        ];
+        let (split_names, groups) = read_subsplits(names.map(String::from).to_vec());
+        assert_eq!(
+            vec!["1-1", "1-2", "1-3", "Bridge", "2-1", "2-2", "3-1"],
+            split_names
+        );
+        assert_eq!(
+            vec![
+                ("World 1".to_string(), 0, 3),
+                ("2-2".to_string(), 4, 2),
+                ("3-1".to_string(), 6, 1),
+            ],
+            groups
+        );
+
+        let mut run = RunData::new("test".into(), split_names);
+        for (name, start, len) in groups {
+            run.add_group(name, start, len).unwrap();
+        }
+        let exported: Vec<String> = (0..names.len()).map(|i| segment_name(&run, i)).collect();
+        assert_eq!("{World 1} 1-3", exported[2]);
+        assert_eq!("{2-2} 2-2", exported[5]);
+        let (_, got_groups) = read_subsplits(exported);
+        assert_eq!(run.groups().len(), got_groups.len());
+    }
+
+    #[test]
+    fn time_format() {
+        let time = Duration::new(93_784, 500_000_000);
+        assert_eq!("1.02:03:04.5000000", format_time(time));
+        assert_eq!(time, parse_time("1.02:03:04.5000000").unwrap());
+        assert_eq!(Duration::from_secs(61), parse_time("00:01:01").unwrap());
+        assert_eq!(
+            "Invalid time `00:61:00` in the LiveSplit file",
+            parse_time("00:61:00").unwrap_err().to_string()
+        );
+        assert!(parse_time("-00:00:01").is_err());
+        assert!(parse_time("18446744073709551615.00:00:00").is_err());
+        assert!(parse_time("99999999999999999:00:00").is_err());
+        assert_eq!(-1.5, parse_signed_time("-00:00:01.5000000").unwrap());
+        assert_eq!("-00:00:01.5000000", format_signed_time(-1.5));
+        assert_eq!("00:00:02.2500000", format_signed_time(2.25));
+    }
+
+    #[test]
+    fn date_time_format() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+        assert_eq!("02/29/2024 12:34:56", format_date_time(time));
+        assert_eq!(Some(time), parse_date_time("02/29/2024 12:34:56"));
+        assert_eq!(
+            Some(SystemTime::UNIX_EPOCH),
+            parse_date_time("01/01/1970 00:00:00")
+        );
+        assert_eq!(None, parse_date_time("13/01/2024 00:00:00"));
+        assert_eq!(None, parse_date_time("01/01/2024"));
+    }
+}
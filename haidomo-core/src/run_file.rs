@@ -0,0 +1,377 @@
+use crate::livesplit::{self, LssError};
+use crate::splits_file::{RunData, RunDataFileError};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+/// Backups `save_run_file` keeps of a splits file unless told otherwise
+pub const DEFAULT_BACKUP_COUNT: usize = 5;
+/// Shape of the time stamp in backup names, where a `0` stands for any digit
+const BACKUP_STAMP: &str = "0000-00-00_00-00-00-000";
+
+/// Temporary files made by `write_atomic` and `replace_file` so far, keeping their names apart
+static TMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Run the frontends start with when they're given no splits file
+pub fn default_run_data() -> RunData {
+    let split_names = (1..4).map(|i| format!("Split-{:02}", i)).collect();
+    let mut run_data = RunData::new(String::from("Ur Mom"), split_names);
+    run_data.metadata_mut().category = String::from("Any%");
+    run_data
+}
+
+/// Why a splits file couldn't be read or written
+#[derive(Debug)]
+pub enum RunFileError {
+    Io(std::io::Error),
+    /// The LiveSplit file couldn't be imported
+    Lss(LssError),
+    /// The .bss file couldn't be read or the run couldn't be turned into one
+    Bss(RunDataFileError),
+}
+impl From<std::io::Error> for RunFileError {
+    fn from(err: std::io::Error) -> Self {
+        RunFileError::Io(err)
+    }
+}
+impl From<LssError> for RunFileError {
+    fn from(err: LssError) -> Self {
+        RunFileError::Lss(err)
+    }
+}
+impl From<RunDataFileError> for RunFileError {
+    fn from(err: RunDataFileError) -> Self {
+        RunFileError::Bss(err)
+    }
+}
+impl std::fmt::Display for RunFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunFileError::Io(err) => write!(f, "Couldn't access the run file: {err}"),
+            RunFileError::Lss(err) => write!(f, "Couldn't import the run: {err}"),
+            RunFileError::Bss(err) => write!(f, "{err}"),
+        }
+    }
+}
+impl std::error::Error for RunFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RunFileError::Io(err) => Some(err),
+            RunFileError::Lss(err) => Some(err),
+            RunFileError::Bss(err) => Some(err),
+        }
+    }
+}
+
+pub fn is_lss_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lss"))
+}
+
+/// File the attempts on the run read from `path` get saved to. LiveSplit files are never
+/// written over: an import goes to a .bss next to it, `None` when that would replace
+/// another run.
+pub fn save_path_for(path: &Path) -> Option<PathBuf> {
+    if !is_lss_path(path) {
+        return Some(path.to_path_buf());
+    }
+    let bss_path = path.with_extension("bss");
+    (!bss_path.exists()).then_some(bss_path)
+}
+
+/// Reads a .bss file, or imports one from LiveSplit if it has the .lss extension
+pub fn read_run_file(path: &Path) -> Result<RunData, RunFileError> {
+    if is_lss_path(path) {
+        let content = std::fs::read_to_string(path)?;
+        return Ok(livesplit::read_lss(&content)?);
+    }
+    let mut file = std::fs::File::open(path)?;
+    Ok(RunData::read_from(&mut file)?)
+}
+
+/// Writes a .bss file, or exports to LiveSplit if it has the .lss extension. The file
+/// is replaced all at once through `write_atomic`.
+pub fn write_run_file(path: &Path, run_data: &RunData) -> Result<(), RunFileError> {
+    let content = if is_lss_path(path) {
+        livesplit::write_lss(run_data).into_bytes()
+    } else {
+        run_data.as_bytes().map_err(RunDataFileError::from)?
+    };
+    Ok(write_atomic(path, &content)?)
+}
+
+/// Writes the splits like `write_run_file`, backing up the file it replaces first when
+/// it wasn't already that day and keeping the latest `keep_backups` backups
+pub fn save_run_file(
+    path: &Path,
+    run_data: &RunData,
+    keep_backups: usize,
+) -> Result<(), RunFileError> {
+    backup_file(path, keep_backups)?;
+    write_run_file(path, run_data)
+}
+
+/// Writes `content` to a temporary file next to `path`, syncs it to disk and renames it
+/// over `path`. A crash mid-write leaves the old file whole instead of half a new one.
+/// The temporary file is named after the process and a count so writes from different
+/// threads or processes don't trip over each other.
+pub fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    write_replacing(path, content, true)?;
+    // The rename is only on disk once the directory it happened in is synced
+    #[cfg(unix)]
+    File::open(parent_dir(path))?.sync_all()?;
+    Ok(())
+}
+
+/// Replaces the file at `path` with `content` through a rename like `write_atomic`, so
+/// readers never see half of it, but without syncing anything to disk. For files that
+/// are rewritten often and don't matter after a crash.
+pub fn replace_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    write_replacing(path, content, false)
+}
+
+fn write_replacing(path: &Path, content: &[u8], durable: bool) -> std::io::Result<()> {
+    let Some(name) = path.file_name() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Not a file path",
+        ));
+    };
+    let mut tmp_name = name.to_owned();
+    let count = TMP_FILES.fetch_add(1, Ordering::Relaxed);
+    tmp_name.push(format!(".{}-{count}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(content)?;
+        if durable {
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp_path, path)
+    };
+    if let Err(err) = write() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Time stamp of a backup made at `time` in UTC, like `2024-02-29_12-34-56-500`. The
+/// stamps sort from oldest to newest.
+fn backup_stamp(time: SystemTime) -> String {
+    let since_epoch = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = since_epoch.as_secs() as i64;
+    let (year, month, day) = livesplit::civil_from_days(secs.div_euclid(86_400));
+    let day_secs = secs.rem_euclid(86_400);
+    format!(
+        "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}-{:03}",
+        day_secs / 3600,
+        (day_secs / 60) % 60,
+        day_secs % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Backup of `path` made at `time`, like `run.bss.2024-02-29_12-34-56-500.bak`
+fn backup_path(path: &Path, time: SystemTime) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(format!(".{}.bak", backup_stamp(time)));
+    path.with_file_name(name)
+}
+
+/// Time stamp of the backup named `backup_name` when it's a backup of the file `name`
+fn stamp_of<'a>(name: &str, backup_name: &'a str) -> Option<&'a str> {
+    let stamp = backup_name
+        .strip_prefix(name)?
+        .strip_prefix('.')?
+        .strip_suffix(".bak")?;
+    let matches = stamp.len() == BACKUP_STAMP.len()
+        && stamp.bytes().zip(BACKUP_STAMP.bytes()).all(|(b, shape)| {
+            if shape == b'0' {
+                b.is_ascii_digit()
+            } else {
+                b == shape
+            }
+        });
+    matches.then_some(stamp)
+}
+
+/// Backups made of the file at `path`, oldest first
+pub fn backups_of(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Ok(Vec::new());
+    };
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(parent_dir(path))? {
+        let entry = entry?;
+        let entry_name = entry.file_name();
+        let Some(entry_name) = entry_name.to_str() else {
+            continue;
+        };
+        if stamp_of(name, entry_name).is_some() {
+            backups.push(path.with_file_name(entry_name));
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+/// Copies the file at `path` to a new timestamped backup next to it, then removes the
+/// oldest backups past `keep`. Only the first save of a day makes a backup, so saving
+/// after every attempt doesn't push the backups worth going back to out. Returns the
+/// backup made, `None` if there was no file to back up, it was backed up that day
+/// already or backups are off with a `keep` of zero.
+pub fn backup_file(path: &Path, keep: usize) -> std::io::Result<Option<PathBuf>> {
+    backup_file_at(path, keep, SystemTime::now())
+}
+
+fn backup_file_at(path: &Path, keep: usize, time: SystemTime) -> std::io::Result<Option<PathBuf>> {
+    if keep == 0 || !path.exists() {
+        return Ok(None);
+    }
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let stamp = backup_stamp(time);
+    // Stamps start with the day they were made on
+    let day = &stamp[.."0000-00-00".len()];
+    let backups = backups_of(path)?;
+    let newest = backups
+        .last()
+        .and_then(|backup| backup.file_name()?.to_str())
+        .and_then(|backup_name| stamp_of(name, backup_name));
+    if newest.is_some_and(|newest| newest.starts_with(day)) {
+        return Ok(None);
+    }
+    let backup = backup_path(path, time);
+    // A backup already made on the same millisecond is left as it is
+    let mut file = match File::options().write(true).create_new(true).open(&backup) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let copied = File::open(path)
+        .and_then(|mut original| std::io::copy(&mut original, &mut file))
+        .and_then(|_| file.sync_all());
+    if let Err(err) = copied {
+        let _ = std::fs::remove_file(&backup);
+        return Err(err);
+    }
+    let backups = backups_of(path)?;
+    for old in &backups[..backups.len().saturating_sub(keep)] {
+        std::fs::remove_file(old)?;
+    }
+    Ok(Some(backup))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("haidomo-run-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let run_data = default_run_data();
+        for name in ["run.bss", "run.LSS"] {
+            let path = dir.join(name);
+            write_run_file(&path, &run_data).unwrap();
+            let read = read_run_file(&path).unwrap();
+            assert_eq!(run_data.metadata(), read.metadata());
+            assert_eq!(run_data.split_names(), read.split_names());
+        }
+        assert!(is_lss_path(Path::new("run.LSS")));
+        assert!(!is_lss_path(Path::new("run.bss")));
+        // The LiveSplit file would go to the .bss written above
+        assert_eq!(None, save_path_for(&dir.join("run.LSS")));
+        assert_eq!(
+            Some(dir.join("other.bss")),
+            save_path_for(&dir.join("other.lss"))
+        );
+        assert_eq!(
+            Some(dir.join("run.bss")),
+            save_path_for(&dir.join("run.bss"))
+        );
+        let tmp_files = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().ends_with(".tmp")
+            })
+            .count();
+        assert_eq!(0, tmp_files);
+
+        std::fs::write(dir.join("bad.lss"), "<Run>").unwrap();
+        assert!(matches!(
+            read_run_file(&dir.join("bad.lss")),
+            Err(RunFileError::Lss(_))
+        ));
+        assert!(matches!(
+            read_run_file(&dir.join("missing.bss")),
+            Err(RunFileError::Io(_))
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn backup_names() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1_709_210_096_500);
+        assert_eq!(
+            Path::new("splits/run.bss.2024-02-29_12-34-56-500.bak"),
+            backup_path(Path::new("splits/run.bss"), time)
+        );
+    }
+
+    #[test]
+    fn rotate_backups() {
+        let dir = std::env::temp_dir().join(format!("haidomo-backups-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.bss");
+        assert_eq!(None, backup_file(&path, 3).unwrap());
+
+        let day =
+            |days: u64| SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(days * 86_400);
+        let mut run_data = default_run_data();
+        for i in 0..5 {
+            run_data.metadata_mut().category = format!("Run {i}");
+            if i > 0 {
+                assert!(backup_file_at(&path, 3, day(i)).unwrap().is_some());
+            }
+            write_run_file(&path, &run_data).unwrap();
+        }
+        let backups = backups_of(&path).unwrap();
+        assert_eq!(3, backups.len());
+        let categories: Vec<String> = backups
+            .iter()
+            .map(|backup| read_run_file(backup).unwrap().metadata().category.clone())
+            .collect();
+        assert_eq!(vec!["Run 1", "Run 2", "Run 3"], categories);
+        assert_eq!("Run 4", read_run_file(&path).unwrap().metadata().category);
+
+        // Saving again on the same day doesn't make another backup
+        let later = day(4) + std::time::Duration::from_secs(3600);
+        assert_eq!(None, backup_file_at(&path, 3, later).unwrap());
+        assert_eq!(backups, backups_of(&path).unwrap());
+
+        // Other files next to the splits are left alone
+        std::fs::write(dir.join("run.bss.journal"), "").unwrap();
+        std::fs::write(dir.join("run.bss.old.bak"), "").unwrap();
+        std::fs::write(dir.join("run.bss.2024-02-29_12-34-56-500.bak.bak"), "").unwrap();
+        std::fs::write(dir.join("other.bss.2024-02-29_12-34-56-500.bak"), "").unwrap();
+        assert_eq!(None, backup_file(&path, 0).unwrap());
+        assert_eq!(backups, backups_of(&path).unwrap());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,518 @@
+use crate::action::Action;
+use crate::run_file::{write_atomic, DEFAULT_BACKUP_COUNT};
+use std::fmt;
+use std::path::PathBuf;
+
+const SETTINGS_FILE_NAME: &str = "settings.cfg";
+
+/// Port the LiveSplit Server component listens on by default
+pub const DEFAULT_SERVER_PORT: u16 = 16834;
+/// Port the event stream listens on by default, right after the control server's
+pub const DEFAULT_EVENTS_PORT: u16 = 16835;
+
+/// Punctuation keys along with their names in the settings file
+const PUNCTUATION: [(char, &str); 14] = [
+    (':', "Colon"),
+    (',', "Comma"),
+    ('\\', "Backslash"),
+    ('/', "Slash"),
+    ('|', "Pipe"),
+    ('?', "Questionmark"),
+    ('[', "OpenBracket"),
+    (']', "CloseBracket"),
+    ('`', "Backtick"),
+    ('-', "Minus"),
+    ('.', "Period"),
+    ('+', "Plus"),
+    ('=', "Equals"),
+    (';', "Semicolon"),
+];
+
+/// Named keys along with their names in the settings file, the first name of a key is
+/// the one it's written with
+const NAMED_KEYS: [(Key, &[&str]); 15] = [
+    (Key::Up, &["Up", "ArrowUp"]),
+    (Key::Down, &["Down", "ArrowDown"]),
+    (Key::Left, &["Left", "ArrowLeft"]),
+    (Key::Right, &["Right", "ArrowRight"]),
+    (Key::Escape, &["Escape", "Esc"]),
+    (Key::Tab, &["Tab"]),
+    (Key::Backspace, &["Backspace"]),
+    (Key::Enter, &["Enter", "Return"]),
+    (Key::Space, &["Space"]),
+    (Key::Insert, &["Insert"]),
+    (Key::Delete, &["Delete"]),
+    (Key::Home, &["Home"]),
+    (Key::End, &["End"]),
+    (Key::PageUp, &["PageUp"]),
+    (Key::PageDown, &["PageDown"]),
+];
+
+/// Name of the action in the settings file
+fn key_name(action: Action) -> &'static str {
+    match action {
+        Action::StartOrSplit => "start_or_split",
+        Action::Pause => "pause",
+        Action::ToggleGameTime => "toggle_game_time",
+        Action::Reset => "reset",
+        Action::Undo => "undo",
+        Action::Skip => "skip",
+        Action::NextComparison => "next_comparison",
+        Action::PrevComparison => "prev_comparison",
+    }
+}
+
+fn action_from_key_name(name: &str) -> Option<Action> {
+    Action::ALL.into_iter().find(|a| key_name(*a) == name)
+}
+
+/// Key on the keyboard, each frontend maps the keys it reads to these
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    /// Letter, digit or punctuation key, letters are always uppercase
+    Char(char),
+    /// Function key, `F(1)` being F1
+    F(u8),
+    Up,
+    Down,
+    Left,
+    Right,
+    Escape,
+    Tab,
+    Backspace,
+    Enter,
+    Space,
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+impl Key {
+    /// Key typing `c`, no matter if it came out in uppercase through Shift or Caps Lock
+    pub fn from_char(c: char) -> Option<Self> {
+        if c == ' ' {
+            return Some(Key::Space);
+        }
+        let c = c.to_ascii_uppercase();
+        let known = c.is_ascii_alphanumeric() || PUNCTUATION.iter().any(|(p, _)| *p == c);
+        known.then_some(Key::Char(c))
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some((key, _)) = NAMED_KEYS.iter().find(|(_, names)| names.contains(&name)) {
+            return Some(*key);
+        }
+        if let Some((c, _)) = PUNCTUATION.iter().find(|(_, p)| *p == name) {
+            return Some(Key::Char(*c));
+        }
+        if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse().ok()) {
+            return (1..=35).contains(&n).then_some(Key::F(n));
+        }
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::from_char(c),
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(c) => match PUNCTUATION.iter().find(|(p, _)| p == c) {
+                Some((_, name)) => write!(f, "{name}"),
+                None => write!(f, "{c}"),
+            },
+            Key::F(n) => write!(f, "F{n}"),
+            key => {
+                let (_, names) = NAMED_KEYS
+                    .iter()
+                    .find(|(named, _)| named == key)
+                    .expect("Every other key has a name");
+                write!(f, "{}", names[0])
+            }
+        }
+    }
+}
+
+/// A key along with the modifiers that have to be held for it, written like `Ctrl+Shift+S`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: Key,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+impl KeyBinding {
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        // `Plus` is written out so it doesn't get taken for a separator
+        let key = Key::from_name(parts.pop()?)?;
+        let mut binding = Self::new(key);
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => binding.ctrl = true,
+                "shift" => binding.shift = true,
+                "alt" => binding.alt = true,
+                _ => return None,
+            }
+        }
+        Some(binding)
+    }
+}
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// Settings shared by the frontends, kept in a file in the user's config directory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Settings {
+    /// Binding of each action in the order of `Action::ALL`, `None` when it's unbound
+    keybindings: [Option<KeyBinding>; Action::ALL.len()],
+    /// Whether to take LiveSplit Server commands over TCP on `server_port`
+    pub server_enabled: bool,
+    pub server_port: u16,
+    /// Whether to push timer events over a WebSocket on `events_port`
+    pub events_enabled: bool,
+    pub events_port: u16,
+    /// Directory the timer state is written to as text files, `None` when it's off
+    pub text_output_dir: Option<PathBuf>,
+    /// Backups kept of a splits file, one from each day it was saved on, none when zero
+    pub backup_count: usize,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        let mut settings = Self {
+            keybindings: [None; Action::ALL.len()],
+            server_enabled: false,
+            server_port: DEFAULT_SERVER_PORT,
+            events_enabled: false,
+            events_port: DEFAULT_EVENTS_PORT,
+            text_output_dir: None,
+            backup_count: DEFAULT_BACKUP_COUNT,
+        };
+        let key = |c| KeyBinding::new(Key::Char(c));
+        settings.set_binding(Action::StartOrSplit, Some(key('S')));
+        settings.set_binding(Action::Pause, Some(KeyBinding::new(Key::Space)));
+        settings.set_binding(Action::ToggleGameTime, Some(key('G')));
+        settings.set_binding(Action::Reset, Some(key('R')));
+        settings.set_binding(Action::Undo, Some(key('U')));
+        settings.set_binding(Action::Skip, Some(key('K')));
+        settings.set_binding(Action::NextComparison, Some(KeyBinding::new(Key::Right)));
+        settings.set_binding(Action::PrevComparison, Some(KeyBinding::new(Key::Left)));
+        settings
+    }
+}
+impl Settings {
+    pub fn binding(&self, action: Action) -> Option<KeyBinding> {
+        self.keybindings[action as usize]
+    }
+
+    /// Binds `action`, any other action on the same binding is left unbound
+    pub fn set_binding(&mut self, action: Action, binding: Option<KeyBinding>) {
+        if binding.is_some() {
+            for other in self.keybindings.iter_mut() {
+                if *other == binding {
+                    *other = None;
+                }
+            }
+        }
+        self.keybindings[action as usize] = binding;
+    }
+
+    /// Puts every action back on its default binding, the other settings are kept
+    pub fn reset_keybindings(&mut self) {
+        self.keybindings = Self::default().keybindings;
+    }
+
+    /// Action bound to the keys `pressed`. The modifiers have to match exactly so `S`
+    /// doesn't go off on `Ctrl+S`.
+    pub fn action_for(&self, pressed: KeyBinding) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| self.binding(*action) == Some(pressed))
+    }
+
+    /// Reads settings from lines like `reset = Ctrl+R`, where an empty binding leaves the
+    /// action unbound. Settings missing from the text keep their default and lines that
+    /// can't be understood are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut settings = Self::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                eprintln!("[ERROR] Settings line {} is missing a `=`", i + 1);
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+            match name {
+                "server_enabled" => {
+                    match value.parse() {
+                        Ok(enabled) => settings.server_enabled = enabled,
+                        Err(_) => eprintln!("[ERROR] Invalid `{value}` on line {}", i + 1),
+                    }
+                    continue;
+                }
+                "server_port" => {
+                    match value.parse() {
+                        Ok(port) => settings.server_port = port,
+                        Err(_) => eprintln!("[ERROR] Invalid port `{value}` on line {}", i + 1),
+                    }
+                    continue;
+                }
+                "events_enabled" => {
+                    match value.parse() {
+                        Ok(enabled) => settings.events_enabled = enabled,
+                        Err(_) => eprintln!("[ERROR] Invalid `{value}` on line {}", i + 1),
+                    }
+                    continue;
+                }
+                "events_port" => {
+                    match value.parse() {
+                        Ok(port) => settings.events_port = port,
+                        Err(_) => eprintln!("[ERROR] Invalid port `{value}` on line {}", i + 1),
+                    }
+                    continue;
+                }
+                "text_output_dir" => {
+                    settings.text_output_dir = (!value.is_empty()).then(|| PathBuf::from(value));
+                    continue;
+                }
+                "backup_count" => {
+                    match value.parse() {
+                        Ok(count) => settings.backup_count = count,
+                        Err(_) => eprintln!("[ERROR] Invalid count `{value}` on line {}", i + 1),
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let Some(action) = action_from_key_name(name) else {
+                eprintln!("[ERROR] Unknown setting `{name}` on line {}", i + 1);
+                continue;
+            };
+            if value.is_empty() {
+                settings.keybindings[action as usize] = None;
+                continue;
+            }
+            match KeyBinding::parse(value) {
+                Some(binding) => settings.set_binding(action, Some(binding)),
+                None => eprintln!("[ERROR] Invalid key binding `{value}` on line {}", i + 1),
+            }
+        }
+        settings
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# Hai Domo! settings\n");
+        for action in Action::ALL {
+            let binding = self
+                .binding(action)
+                .map(|binding| binding.to_string())
+                .unwrap_or_default();
+            text.push_str(&format!("{} = {binding}\n", key_name(action)));
+        }
+        text.push_str(&format!("server_enabled = {}\n", self.server_enabled));
+        text.push_str(&format!("server_port = {}\n", self.server_port));
+        text.push_str(&format!("events_enabled = {}\n", self.events_enabled));
+        text.push_str(&format!("events_port = {}\n", self.events_port));
+        let text_output_dir = self
+            .text_output_dir
+            .as_ref()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_default();
+        text.push_str(&format!("text_output_dir = {text_output_dir}\n"));
+        text.push_str(&format!("backup_count = {}\n", self.backup_count));
+        text
+    }
+
+    /// Path of the settings file in the user's config directory
+    pub fn file_path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("haidomo").join(SETTINGS_FILE_NAME))
+    }
+
+    /// Loads the settings file, falling back to the defaults when there's none
+    pub fn load() -> Self {
+        let Some(path) = Self::file_path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                println!("[INFO] Loaded settings from {}", path.display());
+                Self::parse(&text)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => {
+                eprintln!("[ERROR] Failed to read {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<PathBuf, String> {
+        let path = Self::file_path().ok_or("Couldn't find the config directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| format!("{err:?}"))?;
+        }
+        write_atomic(&path, self.to_text().as_bytes()).map_err(|err| format!("{err:?}"))?;
+        Ok(path)
+    }
+}
+
+fn config_dir() -> Option<PathBuf> {
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+    };
+    if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip() {
+        let mut settings = Settings::default();
+        settings.set_binding(
+            Action::Reset,
+            Some(KeyBinding::parse("Ctrl+Shift+R").unwrap()),
+        );
+        settings.set_binding(Action::Skip, None);
+        settings.set_binding(Action::Undo, Some(KeyBinding::parse("Alt+Plus").unwrap()));
+        settings.server_enabled = true;
+        settings.server_port = 9000;
+        settings.events_enabled = true;
+        settings.events_port = 9001;
+        settings.text_output_dir = Some(PathBuf::from("/tmp/haidomo text"));
+        settings.backup_count = 0;
+        let got = Settings::parse(&settings.to_text());
+        assert_eq!(settings, got);
+        assert_eq!(None, got.binding(Action::Skip));
+        assert_eq!(
+            "Ctrl+Shift+R",
+            got.binding(Action::Reset).unwrap().to_string()
+        );
+        assert_eq!("Alt+Plus", got.binding(Action::Undo).unwrap().to_string());
+    }
+
+    #[test]
+    fn parse_settings() {
+        let text = "\
+# comment
+pause = alt+P
+undo =
+not_an_action = S
+skip = Ctrl+NotAKey
+next_comparison = S
+prev_comparison = ArrowUp
+server_port = 99999
+backup_count = -1
+text_output_dir =
+";
+        let settings = Settings::parse(text);
+        assert_eq!(
+            Some(KeyBinding {
+                key: Key::Char('P'),
+                ctrl: false,
+                shift: false,
+                alt: true,
+            }),
+            settings.binding(Action::Pause)
+        );
+        assert_eq!(None, settings.binding(Action::Undo));
+        // Invalid bindings keep the default
+        assert_eq!(
+            Some(KeyBinding::new(Key::Char('K'))),
+            settings.binding(Action::Skip)
+        );
+        // Taking over a key unbinds the action that had it
+        assert_eq!(
+            Some(KeyBinding::new(Key::Char('S'))),
+            settings.binding(Action::NextComparison)
+        );
+        assert_eq!(None, settings.binding(Action::StartOrSplit));
+        assert_eq!(
+            Some(KeyBinding::new(Key::Up)),
+            settings.binding(Action::PrevComparison)
+        );
+        assert_eq!(DEFAULT_SERVER_PORT, settings.server_port);
+        assert_eq!(None, settings.text_output_dir);
+        assert_eq!(DEFAULT_BACKUP_COUNT, settings.backup_count);
+    }
+
+    #[test]
+    fn key_names() {
+        for (name, key) in [
+            ("S", Key::Char('S')),
+            ("7", Key::Char('7')),
+            ("Minus", Key::Char('-')),
+            ("F12", Key::F(12)),
+            ("Right", Key::Right),
+            ("Space", Key::Space),
+        ] {
+            assert_eq!(Some(key), Key::from_name(name));
+            assert_eq!(name, key.to_string());
+        }
+        assert_eq!(Some(Key::Char('S')), Key::from_name("s"));
+        assert_eq!(Some(Key::Char('S')), Key::from_char('s'));
+        assert_eq!(Some(Key::Char('-')), Key::from_name("-"));
+        assert_eq!(None, Key::from_name("F0"));
+        assert_eq!(None, Key::from_char('é'));
+    }
+
+    #[test]
+    fn reset_keybindings() {
+        let mut settings = Settings::default();
+        settings.set_binding(Action::Reset, None);
+        settings.server_enabled = true;
+        settings.backup_count = 0;
+        settings.reset_keybindings();
+        assert_eq!(
+            Some(KeyBinding::new(Key::Char('R'))),
+            settings.binding(Action::Reset)
+        );
+        assert!(settings.server_enabled);
+        assert_eq!(0, settings.backup_count);
+    }
+
+    #[test]
+    fn match_modifiers() {
+        let settings = Settings::default();
+        let s = KeyBinding::new(Key::Char('S'));
+        assert_eq!(Some(Action::StartOrSplit), settings.action_for(s));
+        let ctrl_s = KeyBinding { ctrl: true, ..s };
+        assert_eq!(None, settings.action_for(ctrl_s));
+    }
+}
@@ -0,0 +1,2834 @@
+use crate::stopwatch::TimingMethod;
+use std::time::{Duration, SystemTime};
+
+macro_rules! push_number_bytes {
+    ($vec: expr, $number: expr) => {
+        for b in $number.to_le_bytes() {
+            $vec.push(b);
+        }
+    };
+}
+macro_rules! push_str_bytes {
+    ($vec: expr, $str: expr) => {
+        for b in $str.clone().into_bytes() {
+            $vec.push(b);
+        }
+    };
+}
+macro_rules! read_number_bytes {
+    ($bytes: expr, $offset: expr, $type: ty) => {{
+        const SIZE: usize = std::mem::size_of::<$type>();
+        match $bytes.get($offset..$offset + SIZE) {
+            None => None,
+            Some(b) => {
+                $offset += SIZE;
+                Some(<$type>::from_le_bytes(b.try_into().expect(concat!(
+                    "Should be able to turn slice into bytes for ",
+                    stringify!($type)
+                ))))
+            }
+        }
+    }};
+}
+
+const VERSION: u8 = 0b00001001;
+const SIGNATURE: [u8; 4] = [b'b', b's', b's', 69];
+
+/// From this version on the signature and version are followed by chunks, each being a
+/// tag, the payload length as a u32, the payload and a CRC-32 of all three. Unknown
+/// chunks are kept as they are and written back after the known ones.
+const CHUNKED_VERSION: u8 = 9;
+const CHUNK_GAME: [u8; 4] = *b"GAME";
+const CHUNK_SPLITS: [u8; 4] = *b"SPLT";
+const CHUNK_ATTEMPTS: [u8; 4] = *b"ATMP";
+const CHUNK_BEST_SEGMENTS: [u8; 4] = *b"BEST";
+const CHUNK_METADATA: [u8; 4] = *b"META";
+const CHUNK_GROUPS: [u8; 4] = *b"GRPS";
+const CHUNK_START_OFFSET: [u8; 4] = *b"OFST";
+
+const BEST_REAL_TIME: u8 = 0b00000001;
+const BEST_GAME_TIME: u8 = 0b00000010;
+
+const HAS_STARTED_AT: u8 = 0b00000001;
+const HAS_ENDED_AT: u8 = 0b00000010;
+
+const USES_EMULATOR: u8 = 0b00000001;
+
+/// Appends `value` as an unsigned LEB128 varint: 7 bits per byte, with the high bit
+/// set when another byte follows. Anything under 128 takes a single byte.
+fn push_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let b = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(b);
+            return;
+        }
+        bytes.push(b | 0x80);
+    }
+}
+
+fn read_varint(content: &[u8], offset: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    let mut shift = 0u32;
+    loop {
+        let b = *content.get(*offset)?;
+        *offset += 1;
+        if shift > 63 || (shift == 63 && b & 0x7F > 1) {
+            // Doesn't fit in a u64
+            return None;
+        }
+        value |= ((b & 0x7F) as u64) << shift;
+        if b & 0x80 == 0 {
+            return Some(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Reads a length field. Version 0 files store them as a single byte, every later
+/// version as a varint.
+fn read_len(content: &[u8], offset: &mut usize, version: u8) -> Option<usize> {
+    if version == 0 {
+        let len = *content.get(*offset)?;
+        *offset += 1;
+        Some(len as usize)
+    } else {
+        usize::try_from(read_varint(content, offset)?).ok()
+    }
+}
+
+/// CRC-32 with the polynomial used by zlib and PNG
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for b in bytes {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Tag of a chunk as text, for errors
+fn chunk_name(tag: &[u8]) -> String {
+    String::from_utf8_lossy(tag).into_owned()
+}
+
+/// What's in `content` from `offset` on, for errors
+fn found_at(content: &[u8], offset: usize) -> String {
+    let rest = content.get(offset..).unwrap_or_default();
+    if rest.is_empty() {
+        return String::from("the end of the data");
+    }
+    format!(
+        "{} bytes starting with {:02x?}",
+        rest.len(),
+        &rest[..rest.len().min(8)]
+    )
+}
+
+fn push_chunk(bytes: &mut Vec<u8>, tag: [u8; 4], payload: &[u8]) -> Result<(), SerializeErr> {
+    let len = u32::try_from(payload.len()).map_err(|_| SerializeErr::ChunkTooBig {
+        chunk: chunk_name(&tag),
+        len: payload.len(),
+    })?;
+    let start = bytes.len();
+    bytes.extend_from_slice(&tag);
+    push_number_bytes!(bytes, len);
+    bytes.extend_from_slice(payload);
+    let crc = crc32(&bytes[start..]);
+    push_number_bytes!(bytes, crc);
+    Ok(())
+}
+
+/// Tag of a chunk and where its payload is in the file
+type Chunk = ([u8; 4], std::ops::Range<usize>);
+
+/// Splits everything from `offset` on into chunks, checking that none is cut short or
+/// corrupted
+fn read_chunks(content: &[u8], mut offset: usize) -> Result<Vec<Chunk>, ParseErr> {
+    let mut chunks = Vec::new();
+    while offset < content.len() {
+        let start = offset;
+        let truncated = |expected: usize| ParseErr::TruncatedChunk {
+            chunk: chunk_name(&content[start..content.len().min(start + 4)]),
+            offset: start,
+            expected,
+            found: content.len() - start,
+        };
+        // Tag and length
+        let header_len = 4 + 4;
+        let tag: [u8; 4] = content
+            .get(offset..offset + 4)
+            .and_then(|tag| tag.try_into().ok())
+            .ok_or_else(|| truncated(header_len))?;
+        offset += 4;
+        let len = read_number_bytes!(content, offset, u32).ok_or_else(|| truncated(header_len))?;
+        let chunk_len = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_add(header_len + 4))
+            .unwrap_or(usize::MAX);
+        if content.len() - start < chunk_len {
+            return Err(truncated(chunk_len));
+        }
+        let payload = offset..start + chunk_len - 4;
+        offset = payload.end;
+        let crc = read_number_bytes!(content, offset, u32).ok_or_else(|| truncated(chunk_len))?;
+        let expected = crc32(&content[start..payload.end]);
+        if expected != crc {
+            return Err(ParseErr::ChecksumMismatch {
+                chunk: chunk_name(&tag),
+                offset: start,
+                expected,
+                found: crc,
+            });
+        }
+        chunks.push((tag, payload));
+    }
+    Ok(chunks)
+}
+
+fn sum_split_durations(split_durations: Vec<Option<Duration>>) -> (Duration, Vec<Option<f64>>) {
+    let mut total_duration = Duration::ZERO;
+    let mut split_times: Vec<Option<f64>> = Vec::new();
+    for sd in split_durations.into_iter() {
+        total_duration += sd.unwrap_or_default();
+        split_times.push(sd.map(|sd| sd.as_secs_f64()));
+    }
+    (total_duration, split_times)
+}
+
+/// Reads a length followed by that many bytes of UTF-8. `what` says what the text is
+/// for errors.
+fn read_text(
+    content: &[u8],
+    offset: &mut usize,
+    version: u8,
+    (section, item): (Section, Option<usize>),
+    what: &str,
+) -> Result<String, ParseErr> {
+    let start = *offset;
+    let len = read_len(content, offset, version).ok_or_else(|| {
+        ParseErr::invalid(
+            (section, item),
+            start,
+            format!("the length of {what}"),
+            found_at(content, start),
+        )
+    })?;
+    let bytes = offset
+        .checked_add(len)
+        .and_then(|end| content.get(*offset..end))
+        .ok_or_else(|| {
+            ParseErr::invalid(
+                (section, item),
+                *offset,
+                format!("{len} bytes of {what}"),
+                found_at(content, *offset),
+            )
+        })?;
+    let text = String::from_utf8(bytes.to_vec()).map_err(|_| ParseErr::InvalidUtf8 {
+        section,
+        offset: *offset,
+        item,
+    })?;
+    *offset += len;
+    Ok(text)
+}
+
+fn read_splits(content: &[u8], offset: &mut usize, version: u8) -> Result<Vec<String>, ParseErr> {
+    let start = *offset;
+    let chunk_len = read_len(content, offset, version).ok_or_else(|| {
+        ParseErr::invalid(
+            (Section::Splits, None),
+            start,
+            "the splits count",
+            found_at(content, start),
+        )
+    })?;
+    let mut splits = Vec::with_capacity(chunk_len.min(content.len()));
+    for i in 0..chunk_len {
+        let split_name = read_text(
+            content,
+            offset,
+            version,
+            (Section::Splits, Some(i)),
+            "the split name",
+        )?;
+        splits.push(split_name);
+    }
+    Ok(splits)
+}
+
+fn read_attempts(
+    content: &[u8],
+    offset: &mut usize,
+    version: u8,
+    splits_count: usize,
+) -> Result<Vec<AttemptData>, ParseErr> {
+    let start = *offset;
+    let chunk_len = read_len(content, offset, version).ok_or_else(|| {
+        ParseErr::invalid(
+            (Section::Attempts, None),
+            start,
+            "the attempts count",
+            found_at(content, start),
+        )
+    })?;
+    let mut attempts = Vec::with_capacity(chunk_len.min(content.len()));
+    // Every attempt is kept, even those that reached no split. The reader before version 1
+    // skipped those while still counting them, so the count and the attempts read didn't
+    // match.
+    for i in 0..chunk_len {
+        let (total_duration, split_times) = read_attempt_times(
+            content,
+            offset,
+            version,
+            splits_count,
+            (i, TimingMethod::RealTime),
+        )?;
+        // Game time was introduced on version 2
+        let game_times = if version >= 2 {
+            read_attempt_times(
+                content,
+                offset,
+                version,
+                splits_count,
+                (i, TimingMethod::GameTime),
+            )?
+        } else {
+            (Duration::ZERO, Vec::new())
+        };
+        // Before version 5 the only way to tell was whether the last split was reached
+        let outcome = if split_times.len() == splits_count {
+            AttemptOutcome::Completed
+        } else {
+            AttemptOutcome::Reset
+        };
+        let mut attempt = AttemptData::new(outcome, (total_duration, split_times), game_times);
+        if version >= 5 {
+            read_attempt_info(content, offset, i, &mut attempt)?;
+        }
+        attempts.push(attempt);
+    }
+    Ok(attempts)
+}
+
+/// Reads the total duration and split times of attempt `attempt` for a single timing
+/// method
+fn read_attempt_times(
+    content: &[u8],
+    offset: &mut usize,
+    version: u8,
+    splits_count: usize,
+    (attempt, method): (usize, TimingMethod),
+) -> Result<(Duration, Vec<Option<f64>>), ParseErr> {
+    let place = (Section::Attempts, Some(attempt));
+    let method = method.label();
+    let total_duration = read_duration(
+        content,
+        offset,
+        attempt,
+        &format!("the {method} total duration"),
+    )?;
+    let start = *offset;
+    let splits_used_count = read_len(content, offset, version).ok_or_else(|| {
+        ParseErr::invalid(
+            place,
+            start,
+            format!("the {method} split times count"),
+            found_at(content, start),
+        )
+    })?;
+    if splits_used_count > splits_count {
+        return Err(ParseErr::invalid(
+            place,
+            start,
+            format!("at most {splits_count} {method} split times"),
+            splits_used_count.to_string(),
+        ));
+    }
+    let mut split_times = Vec::with_capacity(splits_used_count);
+    for i in 0..splits_used_count {
+        let seconds = read_number_bytes!(content, *offset, f64).ok_or_else(|| {
+            ParseErr::invalid(
+                place,
+                *offset,
+                format!("the {method} time of split {i}"),
+                found_at(content, *offset),
+            )
+        })?;
+        // Skipped splits are stored as NaN since version 4
+        split_times.push(Some(seconds).filter(|secs| !secs.is_nan()));
+    }
+    Ok((total_duration, split_times))
+}
+
+/// Writes the total duration and split times of an attempt for a single timing method.
+/// Fails with the amount of split times when there are more than `splits_count`.
+fn push_attempt_times(
+    bytes: &mut Vec<u8>,
+    total_duration: Duration,
+    split_times: &[Option<f64>],
+    splits_count: usize,
+) -> Result<(), usize> {
+    // Total time
+    push_duration(bytes, total_duration);
+    // Splits Used
+    let splits_used = split_times.len();
+    if splits_used > splits_count {
+        return Err(splits_used);
+    }
+    push_varint(bytes, splits_used as u64);
+    for secs in split_times.iter() {
+        push_number_bytes!(bytes, secs.unwrap_or(f64::NAN));
+    }
+    Ok(())
+}
+
+/// Writes a duration as whole seconds followed by the nanoseconds
+fn push_duration(bytes: &mut Vec<u8>, duration: Duration) {
+    push_number_bytes!(bytes, duration.as_secs());
+    push_number_bytes!(bytes, duration.subsec_nanos());
+}
+
+/// Reads a duration of attempt `attempt`, `what` saying which one for errors
+fn read_duration(
+    content: &[u8],
+    offset: &mut usize,
+    attempt: usize,
+    what: &str,
+) -> Result<Duration, ParseErr> {
+    let place = (Section::Attempts, Some(attempt));
+    let start = *offset;
+    let seconds = read_number_bytes!(content, *offset, u64);
+    let nanos_offset = *offset;
+    let nanos = read_number_bytes!(content, *offset, u32);
+    let (Some(seconds), Some(nanos)) = (seconds, nanos) else {
+        return Err(ParseErr::invalid(
+            place,
+            start,
+            what,
+            found_at(content, start),
+        ));
+    };
+    if nanos >= 1_000_000_000 {
+        return Err(ParseErr::invalid(
+            place,
+            nanos_offset,
+            format!("less than a second of nanoseconds in {what}"),
+            format!("{nanos} nanoseconds"),
+        ));
+    }
+    Ok(Duration::new(seconds, nanos))
+}
+
+/// Writes when the attempt started and ended, how it ended and the time spent paused.
+/// Timestamps are durations since the unix epoch behind a flags byte saying which are
+/// known.
+fn push_attempt_info(bytes: &mut Vec<u8>, attempt: &AttemptData) {
+    let since_epoch = |time: SystemTime| {
+        time.duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+    };
+    let mut flags = 0u8;
+    if attempt.started_at.is_some() {
+        flags |= HAS_STARTED_AT;
+    }
+    if attempt.ended_at.is_some() {
+        flags |= HAS_ENDED_AT;
+    }
+    bytes.push(flags);
+    for time in [attempt.started_at, attempt.ended_at].into_iter().flatten() {
+        push_duration(bytes, since_epoch(time));
+    }
+    bytes.push(attempt.outcome as u8);
+    push_varint(bytes, attempt.pause_count as u64);
+    push_duration(bytes, attempt.paused_duration);
+}
+
+fn read_attempt_info(
+    content: &[u8],
+    offset: &mut usize,
+    index: usize,
+    attempt: &mut AttemptData,
+) -> Result<(), ParseErr> {
+    let place = (Section::Attempts, Some(index));
+    let missing = |offset: usize, what: &str| {
+        ParseErr::invalid(place, offset, what, found_at(content, offset))
+    };
+    let flags =
+        read_number_bytes!(content, *offset, u8).ok_or_else(|| missing(*offset, "the flags"))?;
+    let mut read_timestamp = |flag: u8, what: &str| -> Result<Option<SystemTime>, ParseErr> {
+        if flags & flag == 0 {
+            return Ok(None);
+        }
+        let start = *offset;
+        let since_epoch = read_duration(content, offset, index, what)?;
+        let time = SystemTime::UNIX_EPOCH
+            .checked_add(since_epoch)
+            .ok_or_else(|| {
+                ParseErr::invalid(
+                    place,
+                    start,
+                    format!("{what} as a time the system can hold"),
+                    format!("{} seconds since the unix epoch", since_epoch.as_secs()),
+                )
+            })?;
+        Ok(Some(time))
+    };
+    attempt.started_at = read_timestamp(HAS_STARTED_AT, "the start time")?;
+    attempt.ended_at = read_timestamp(HAS_ENDED_AT, "the end time")?;
+    let start = *offset;
+    let outcome =
+        read_number_bytes!(content, *offset, u8).ok_or_else(|| missing(start, "the outcome"))?;
+    attempt.outcome = AttemptOutcome::from_byte(outcome).ok_or_else(|| {
+        ParseErr::invalid(
+            place,
+            start,
+            "the outcome as 0, 1 or 2",
+            outcome.to_string(),
+        )
+    })?;
+    let start = *offset;
+    let pause_count =
+        read_varint(content, offset).ok_or_else(|| missing(start, "the pause count"))?;
+    attempt.pause_count = u32::try_from(pause_count).map_err(|_| {
+        ParseErr::invalid(
+            place,
+            start,
+            "a pause count that fits in 32 bits",
+            pause_count.to_string(),
+        )
+    })?;
+    attempt.paused_duration = read_duration(content, offset, index, "the paused duration")?;
+    Ok(())
+}
+
+fn push_len_str(bytes: &mut Vec<u8>, text: &str) {
+    push_varint(bytes, text.len() as u64);
+    bytes.extend_from_slice(text.as_bytes());
+}
+
+fn read_start_offset(content: &[u8], offset: &mut usize) -> Result<f64, ParseErr> {
+    let start = *offset;
+    let place = (Section::StartOffset, None);
+    let secs = read_number_bytes!(content, *offset, f64).ok_or_else(|| {
+        ParseErr::invalid(place, start, "the start offset", found_at(content, start))
+    })?;
+    if !secs.is_finite() {
+        return Err(ParseErr::invalid(
+            place,
+            start,
+            "a finite amount of seconds",
+            secs.to_string(),
+        ));
+    }
+    Ok(secs)
+}
+
+/// Writes everything in the metadata but the game name, which is in the header
+fn push_metadata(bytes: &mut Vec<u8>, metadata: &RunMetadata) {
+    push_len_str(bytes, &metadata.category);
+    push_len_str(bytes, &metadata.platform);
+    push_len_str(bytes, &metadata.region);
+    let mut flags = 0u8;
+    if metadata.uses_emulator {
+        flags |= USES_EMULATOR;
+    }
+    bytes.push(flags);
+    push_varint(bytes, metadata.variables.len() as u64);
+    for (name, value) in metadata.variables.iter() {
+        push_len_str(bytes, name);
+        push_len_str(bytes, value);
+    }
+}
+
+fn read_metadata(content: &[u8], offset: &mut usize, version: u8) -> Result<RunMetadata, ParseErr> {
+    let place = (Section::Metadata, None);
+    let category = read_text(content, offset, version, place, "the category")?;
+    let platform = read_text(content, offset, version, place, "the platform")?;
+    let region = read_text(content, offset, version, place, "the region")?;
+    let start = *offset;
+    let flags = read_number_bytes!(content, *offset, u8)
+        .ok_or_else(|| ParseErr::invalid(place, start, "the flags", found_at(content, start)))?;
+    let start = *offset;
+    let variables_count = read_len(content, offset, version).ok_or_else(|| {
+        ParseErr::invalid(
+            place,
+            start,
+            "the variables count",
+            found_at(content, start),
+        )
+    })?;
+    let mut variables = Vec::new();
+    for i in 0..variables_count {
+        let place = (Section::Metadata, Some(i));
+        let name = read_text(content, offset, version, place, "the variable name")?;
+        let value = read_text(content, offset, version, place, "the variable value")?;
+        variables.push((name, value));
+    }
+    Ok(RunMetadata {
+        game: String::new(),
+        category,
+        platform,
+        region,
+        uses_emulator: flags & USES_EMULATOR != 0,
+        variables,
+    })
+}
+
+/// Part of a run file, to tell where parse errors are
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    GameName,
+    Splits,
+    Attempts,
+    BestSegments,
+    Metadata,
+    Groups,
+    StartOffset,
+}
+impl Section {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Section::GameName => "game name",
+            Section::Splits => "splits",
+            Section::Attempts => "attempts",
+            Section::BestSegments => "best segments",
+            Section::Metadata => "metadata",
+            Section::Groups => "split groups",
+            Section::StartOffset => "start offset",
+        }
+    }
+
+    /// What the item indices of errors in the section count
+    pub fn item_label(&self) -> &'static str {
+        match self {
+            Section::Splits | Section::BestSegments => "split",
+            Section::Attempts => "attempt",
+            Section::Metadata => "variable",
+            Section::Groups => "group",
+            Section::GameName | Section::StartOffset => "item",
+        }
+    }
+}
+
+/// Why a run file couldn't be read. Offsets are in bytes from the start of the file.
+#[derive(Debug)]
+pub enum ParseErr {
+    /// The file is shorter than the signature and version
+    InvalidHeaderLength {
+        len: usize,
+    },
+    InvalidSignature {
+        found: [u8; 4],
+    },
+    /// The game name is empty
+    InvalidRunName {
+        offset: usize,
+    },
+    /// A chunk needing `expected` bytes from its start has only `found` left in the file
+    TruncatedChunk {
+        chunk: String,
+        offset: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The checksum stored for a chunk doesn't match the one of its content
+    ChecksumMismatch {
+        chunk: String,
+        offset: usize,
+        expected: u32,
+        found: u32,
+    },
+    /// A required chunk isn't there, `offset` being the end of the chunks
+    MissingChunk {
+        chunk: &'static str,
+        offset: usize,
+    },
+    /// A value in a section isn't what it should be. `item` is the index of the split,
+    /// attempt, variable or group it belongs to.
+    InvalidValue {
+        section: Section,
+        offset: usize,
+        item: Option<usize>,
+        expected: String,
+        found: String,
+    },
+    InvalidUtf8 {
+        section: Section,
+        offset: usize,
+        item: Option<usize>,
+    },
+}
+impl ParseErr {
+    fn invalid(
+        (section, item): (Section, Option<usize>),
+        offset: usize,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        ParseErr::InvalidValue {
+            section,
+            offset,
+            item,
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    /// Where in the file it went wrong
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseErr::InvalidHeaderLength { len } => *len,
+            ParseErr::InvalidSignature { .. } => 0,
+            ParseErr::InvalidRunName { offset }
+            | ParseErr::TruncatedChunk { offset, .. }
+            | ParseErr::ChecksumMismatch { offset, .. }
+            | ParseErr::MissingChunk { offset, .. }
+            | ParseErr::InvalidValue { offset, .. }
+            | ParseErr::InvalidUtf8 { offset, .. } => *offset,
+        }
+    }
+}
+impl std::fmt::Display for ParseErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let in_section = |section: &Section, item: &Option<usize>| match item {
+            Some(item) => format!("{} ({} {item})", section.label(), section.item_label()),
+            None => section.label().to_string(),
+        };
+        match self {
+            ParseErr::InvalidHeaderLength { len } => write!(
+                f,
+                "The file has {len} bytes, too few for the header of a run file"
+            ),
+            ParseErr::InvalidSignature { found } => write!(
+                f,
+                "Not a run file, expected the signature {SIGNATURE:02x?} but found {found:02x?}"
+            ),
+            ParseErr::InvalidRunName { offset } => {
+                write!(f, "The game name at byte {offset} is empty")
+            }
+            ParseErr::TruncatedChunk {
+                chunk,
+                offset,
+                expected,
+                found,
+            } => write!(
+                f,
+                "The {chunk} chunk at byte {offset} is cut short, expected {expected} bytes but found {found}"
+            ),
+            ParseErr::ChecksumMismatch {
+                chunk,
+                offset,
+                expected,
+                found,
+            } => write!(
+                f,
+                "The {chunk} chunk at byte {offset} is corrupted, expected the checksum {expected:#010x} but found {found:#010x}"
+            ),
+            ParseErr::MissingChunk { chunk, offset } => {
+                write!(f, "No {chunk} chunk in the {offset} bytes of the file")
+            }
+            ParseErr::InvalidValue {
+                section,
+                offset,
+                item,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Invalid {} at byte {offset}, expected {expected} but found {found}",
+                in_section(section, item)
+            ),
+            ParseErr::InvalidUtf8 {
+                section,
+                offset,
+                item,
+            } => write!(
+                f,
+                "Invalid {} at byte {offset}, expected UTF-8 text",
+                in_section(section, item)
+            ),
+        }
+    }
+}
+impl std::error::Error for ParseErr {}
+
+/// Why a run couldn't be turned into bytes
+#[derive(Debug, PartialEq, Eq)]
+pub enum SerializeErr {
+    MissingGameName,
+    /// Attempt `attempt` has times for more splits than the run has
+    TooManySplitTimes {
+        attempt: usize,
+        method: TimingMethod,
+        splits_count: usize,
+        found: usize,
+    },
+    /// The run was read from a newer version of the format, which can't be written
+    /// without losing what that version added
+    NewerVersion {
+        version: u8,
+    },
+    /// A chunk is longer than its length can say
+    ChunkTooBig {
+        chunk: String,
+        len: usize,
+    },
+}
+impl std::fmt::Display for SerializeErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SerializeErr::MissingGameName => write!(f, "The run needs a game name to be saved!"),
+            SerializeErr::TooManySplitTimes {
+                attempt,
+                method,
+                splits_count,
+                found,
+            } => write!(
+                f,
+                "Attempt {attempt} has more {} split times than the run holds! Max splits used per attempt is {splits_count} but attempt says it used {found}!",
+                method.label()
+            ),
+            SerializeErr::NewerVersion { version } => write!(
+                f,
+                "The run was read from version {version} of the format, saving it here would lose what that version added!"
+            ),
+            SerializeErr::ChunkTooBig { chunk, len } => write!(
+                f,
+                "The {chunk} chunk is too big to be saved! It has {len} bytes"
+            ),
+        }
+    }
+}
+impl std::error::Error for SerializeErr {}
+
+/// Why an edit of the splits or groups was refused
+#[derive(Debug, PartialEq, Eq)]
+pub enum EditErr {
+    IndexOutOfRange,
+    /// The group is empty, goes past the last split or overlaps another group
+    InvalidGroup,
+}
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum RunDataFileError {
+    IOError(std::io::Error),
+    ParseError(ParseErr),
+    SerializeError(SerializeErr),
+}
+impl From<std::io::Error> for RunDataFileError {
+    fn from(err: std::io::Error) -> Self {
+        RunDataFileError::IOError(err)
+    }
+}
+impl From<ParseErr> for RunDataFileError {
+    fn from(err: ParseErr) -> Self {
+        RunDataFileError::ParseError(err)
+    }
+}
+impl From<SerializeErr> for RunDataFileError {
+    fn from(err: SerializeErr) -> Self {
+        RunDataFileError::SerializeError(err)
+    }
+}
+impl std::fmt::Display for RunDataFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunDataFileError::IOError(err) => write!(f, "Couldn't access the run file: {err}"),
+            RunDataFileError::ParseError(err) => write!(f, "Couldn't read the run file: {err}"),
+            RunDataFileError::SerializeError(err) => {
+                write!(f, "Couldn't write the run file: {err}")
+            }
+        }
+    }
+}
+impl std::error::Error for RunDataFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RunDataFileError::IOError(err) => Some(err),
+            RunDataFileError::ParseError(err) => Some(err),
+            RunDataFileError::SerializeError(err) => Some(err),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    PersonalBest,
+    BestSegments,
+}
+impl Comparison {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Comparison::PersonalBest => "Personal Best",
+            Comparison::BestSegments => "Best Segments",
+        }
+    }
+
+    pub const ALL: [Comparison; 2] = [Comparison::PersonalBest, Comparison::BestSegments];
+}
+
+/// What's being run. The game name is required, everything else can be left empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunMetadata {
+    pub game: String,
+    pub category: String,
+    pub platform: String,
+    pub region: String,
+    pub uses_emulator: bool,
+    /// Extra details that set the run apart, like the game version or a subcategory
+    pub variables: Vec<(String, String)>,
+}
+impl RunMetadata {
+    pub fn new(game: String) -> Self {
+        Self {
+            game,
+            ..Default::default()
+        }
+    }
+}
+
+/// Consecutive splits shown together under a header, like the levels of a world
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SplitGroup {
+    name: String,
+    start: usize,
+    len: usize,
+}
+impl SplitGroup {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Indices of the splits in the group
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.start + self.len
+    }
+
+    pub fn contains(&self, split: usize) -> bool {
+        self.range().contains(&split)
+    }
+}
+
+#[derive(Debug)]
+pub struct RunData {
+    version: u8,
+    metadata: RunMetadata,
+    splits: Vec<String>,
+    attempts: Vec<AttemptData>,
+    /// Fastest time ever on each split in seconds as stored in the file, so they're kept
+    /// even if the attempt they came from is gone. The attempts can only improve on them.
+    best_segments: Vec<Option<f64>>,
+    game_best_segments: Vec<Option<f64>>,
+    /// Groups in the order of their splits, they can't overlap
+    groups: Vec<SplitGroup>,
+    /// Seconds on the timer when a run starts, negative to count down to zero first
+    start_offset: f64,
+    /// Tag and payload of the chunks this version doesn't know, in the order they came
+    unknown_chunks: Vec<([u8; 4], Vec<u8>)>,
+}
+/// How an attempt came to an end
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    Completed = 0,
+    /// Reset on purpose before reaching the end
+    Reset = 1,
+    /// Left unfinished without a reset, like closing the timer mid-run
+    Abandoned = 2,
+}
+impl AttemptOutcome {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(AttemptOutcome::Completed),
+            1 => Some(AttemptOutcome::Reset),
+            2 => Some(AttemptOutcome::Abandoned),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AttemptData {
+    total_duration: Duration,
+    split_times: Vec<Option<f64>>,
+    game_total_duration: Duration,
+    game_split_times: Vec<Option<f64>>,
+    outcome: AttemptOutcome,
+    /// Wall-clock times of the start and end, unknown for attempts from before version 5
+    started_at: Option<SystemTime>,
+    ended_at: Option<SystemTime>,
+    pause_count: u32,
+    paused_duration: Duration,
+}
+impl AttemptData {
+    fn new(
+        outcome: AttemptOutcome,
+        (total_duration, split_times): (Duration, Vec<Option<f64>>),
+        (game_total_duration, game_split_times): (Duration, Vec<Option<f64>>),
+    ) -> Self {
+        Self {
+            total_duration,
+            split_times,
+            game_total_duration,
+            game_split_times,
+            outcome,
+            started_at: None,
+            ended_at: None,
+            pause_count: 0,
+            paused_duration: Duration::ZERO,
+        }
+    }
+
+    pub fn outcome(&self) -> AttemptOutcome {
+        self.outcome
+    }
+
+    pub fn started_at(&self) -> Option<SystemTime> {
+        self.started_at
+    }
+
+    pub fn ended_at(&self) -> Option<SystemTime> {
+        self.ended_at
+    }
+
+    pub fn set_timestamps(&mut self, started_at: Option<SystemTime>, ended_at: Option<SystemTime>) {
+        self.started_at = started_at;
+        self.ended_at = ended_at;
+    }
+
+    pub fn pause_count(&self) -> u32 {
+        self.pause_count
+    }
+
+    /// Real time spent with the timer paused, not counted in the total duration
+    pub fn paused_duration(&self) -> Duration {
+        self.paused_duration
+    }
+
+    pub fn set_pauses(&mut self, pause_count: u32, paused_duration: Duration) {
+        self.pause_count = pause_count;
+        self.paused_duration = paused_duration;
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.total_duration
+    }
+
+    /// Time spent on each split reached in the attempt, in seconds. Skipped splits are
+    /// `None` and their time counts towards the next split.
+    pub fn split_times(&self) -> &[Option<f64>] {
+        &self.split_times
+    }
+
+    pub fn game_total_duration(&self) -> Duration {
+        self.game_total_duration
+    }
+
+    /// Game time spent on each split reached, empty if game time wasn't tracked
+    pub fn game_split_times(&self) -> &[Option<f64>] {
+        &self.game_split_times
+    }
+
+    pub fn total_duration_for(&self, method: TimingMethod) -> Duration {
+        match method {
+            TimingMethod::RealTime => self.total_duration,
+            TimingMethod::GameTime => self.game_total_duration,
+        }
+    }
+
+    pub fn split_times_for(&self, method: TimingMethod) -> &[Option<f64>] {
+        match method {
+            TimingMethod::RealTime => &self.split_times,
+            TimingMethod::GameTime => &self.game_split_times,
+        }
+    }
+}
+
+impl RunData {
+    pub fn new(game: String, splits_names: Vec<String>) -> Self {
+        let splits_count = splits_names.len();
+        Self {
+            version: VERSION,
+            metadata: RunMetadata::new(game),
+            splits: splits_names,
+            attempts: vec![],
+            best_segments: vec![None; splits_count],
+            game_best_segments: vec![None; splits_count],
+            groups: Vec::new(),
+            start_offset: 0.0,
+            unknown_chunks: Vec::new(),
+        }
+    }
+
+    /// Adds a split at the end of the run, returning its index
+    pub fn add_split(&mut self, split_name: String) -> Result<usize, EditErr> {
+        self.insert_split(self.splits.len(), split_name)
+    }
+
+    /// Inserts a split before the one at `index`. Attempts that went past it never timed
+    /// it, so it's left as skipped on them and the next split keeps the time of both.
+    pub fn insert_split(&mut self, index: usize, split_name: String) -> Result<usize, EditErr> {
+        if index > self.splits.len() {
+            return Err(EditErr::IndexOutOfRange);
+        }
+        self.splits.insert(index, split_name);
+        for group in self.groups.iter_mut() {
+            if index <= group.start {
+                group.start += 1;
+            } else if index < group.start + group.len {
+                group.len += 1;
+            }
+        }
+        self.best_segments.insert(index, None);
+        self.game_best_segments.insert(index, None);
+        for attempt in self.attempts.iter_mut() {
+            let completed = attempt.outcome == AttemptOutcome::Completed;
+            for split_times in [&mut attempt.split_times, &mut attempt.game_split_times] {
+                if split_times.len() > index || (completed && split_times.len() == index) {
+                    split_times.insert(index, None);
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    /// Removes the split at `index`. The time spent on it goes to the split after it, or
+    /// to the one before when it was the last split of the run.
+    pub fn remove_split(&mut self, index: usize) -> Option<String> {
+        if index >= self.splits.len() {
+            return None;
+        }
+        let is_last = index + 1 == self.splits.len();
+        for attempt in self.attempts.iter_mut() {
+            for split_times in [&mut attempt.split_times, &mut attempt.game_split_times] {
+                if index >= split_times.len() {
+                    continue;
+                }
+                let removed = split_times.remove(index);
+                if is_last {
+                    if let (Some(removed), Some(previous)) = (
+                        removed,
+                        index.checked_sub(1).and_then(|i| split_times.get_mut(i)),
+                    ) {
+                        // A skipped split before the last one had its time in it already
+                        *previous = Some(previous.unwrap_or_default() + removed);
+                    }
+                } else if let Some(removed) = removed {
+                    // Skipped splits pass their time on, so it goes to the next timed one.
+                    // Attempts reset before it keep the time in their total only.
+                    if let Some(next) = split_times[index..].iter_mut().flatten().next() {
+                        *next += removed;
+                    }
+                }
+            }
+        }
+        for group in self.groups.iter_mut() {
+            if index < group.start {
+                group.start -= 1;
+            } else if group.contains(index) {
+                group.len -= 1;
+            }
+        }
+        self.groups.retain(|group| group.len > 0);
+        self.best_segments.remove(index);
+        self.game_best_segments.remove(index);
+        // The best of the split that took the time over doesn't hold anymore
+        let merged = if is_last {
+            index.checked_sub(1)
+        } else {
+            Some(index)
+        };
+        if let Some(merged) = merged {
+            self.best_segments[merged] = None;
+            self.game_best_segments[merged] = None;
+        }
+        Some(self.splits.remove(index))
+    }
+
+    pub fn rename_split(&mut self, index: usize, split_name: String) {
+        if let Some(name) = self.splits.get_mut(index) {
+            *name = split_name;
+        }
+    }
+
+    /// Swaps two splits along with their times on every attempt. Attempts that only
+    /// reached the first of them now end before it, as the split there is one they never
+    /// got to. Their total still holds the time they had.
+    pub fn swap_splits(&mut self, a: usize, b: usize) {
+        if a >= self.splits.len() || b >= self.splits.len() || a == b {
+            return;
+        }
+        let (a, b) = (a.min(b), a.max(b));
+        self.splits.swap(a, b);
+        self.best_segments.swap(a, b);
+        self.game_best_segments.swap(a, b);
+        for attempt in self.attempts.iter_mut() {
+            for split_times in [&mut attempt.split_times, &mut attempt.game_split_times] {
+                if b < split_times.len() {
+                    split_times.swap(a, b);
+                } else {
+                    split_times.truncate(a);
+                }
+            }
+        }
+    }
+
+    pub fn groups(&self) -> &[SplitGroup] {
+        &self.groups
+    }
+
+    /// Group the split at `index` belongs to
+    pub fn group_of(&self, index: usize) -> Option<&SplitGroup> {
+        self.groups.iter().find(|group| group.contains(index))
+    }
+
+    /// Groups `len` splits starting at `start`, returning the index of the group. Fails
+    /// if it's empty, goes past the last split or overlaps another group.
+    pub fn add_group(&mut self, name: String, start: usize, len: usize) -> Result<usize, EditErr> {
+        let group = SplitGroup { name, start, len };
+        let overlaps = self
+            .groups
+            .iter()
+            .any(|other| other.start < start + len && start < other.start + other.len);
+        if len == 0 || start + len > self.splits.len() || overlaps {
+            return Err(EditErr::InvalidGroup);
+        }
+        let index = self.groups.partition_point(|other| other.start < start);
+        self.groups.insert(index, group);
+        Ok(index)
+    }
+
+    /// Removes a group, its splits stay in the run
+    pub fn remove_group(&mut self, index: usize) -> Option<SplitGroup> {
+        (index < self.groups.len()).then(|| self.groups.remove(index))
+    }
+
+    pub fn rename_group(&mut self, index: usize, name: String) {
+        if let Some(group) = self.groups.get_mut(index) {
+            group.name = name;
+        }
+    }
+
+    pub fn start_offset(&self) -> f64 {
+        self.start_offset
+    }
+
+    /// Offsets that aren't finite are taken as zero
+    pub fn set_start_offset(&mut self, secs: f64) {
+        self.start_offset = if secs.is_finite() { secs } else { 0.0 };
+    }
+
+    pub fn metadata(&self) -> &RunMetadata {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut RunMetadata {
+        &mut self.metadata
+    }
+
+    pub fn split_names(&self) -> &[String] {
+        &self.splits
+    }
+
+    pub fn attempts(&self) -> &[AttemptData] {
+        &self.attempts
+    }
+
+    pub fn get_indexed_split_names(&self) -> Vec<(usize, String)> {
+        self.splits
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (i, x.clone()))
+            .collect()
+    }
+
+    pub fn get_split_name(&self, index: usize) -> Option<&String> {
+        self.splits.get(index)
+    }
+
+    /// Fastest attempt that reached the end of the run on the given timing method
+    pub fn personal_best(&self, method: TimingMethod) -> Option<&AttemptData> {
+        if self.splits.is_empty() {
+            return None;
+        }
+        self.attempts
+            .iter()
+            .filter(|a| a.split_times_for(method).len() == self.splits.len())
+            .min_by_key(|a| a.total_duration_for(method))
+    }
+
+    /// Amount of attempts that were reset before reaching the end of the run
+    pub fn reset_count(&self) -> usize {
+        self.attempts
+            .iter()
+            .filter(|attempt| attempt.outcome == AttemptOutcome::Reset)
+            .count()
+    }
+
+    /// Fastest time ever on each split, out of the stored best segments and the attempts.
+    /// Splits right after a skipped one hold the time of both, so they don't count.
+    pub fn best_segments_for(&self, method: TimingMethod) -> Vec<Option<f64>> {
+        let mut best_segments = match method {
+            TimingMethod::RealTime => self.best_segments.clone(),
+            TimingMethod::GameTime => self.game_best_segments.clone(),
+        };
+        for attempt in self.attempts.iter() {
+            let mut after_skip = false;
+            for (best, secs) in best_segments
+                .iter_mut()
+                .zip(attempt.split_times_for(method).iter())
+            {
+                match secs {
+                    Some(secs) if !after_skip && best.is_none_or(|best| *secs < best) => {
+                        *best = Some(*secs);
+                    }
+                    _ => {}
+                }
+                after_skip = secs.is_none();
+            }
+        }
+        best_segments
+    }
+
+    /// Sum of the best time ever on every split, `None` while any split lacks one
+    pub fn sum_of_best(&self, method: TimingMethod) -> Option<Duration> {
+        if self.splits.is_empty() {
+            return None;
+        }
+        self.best_segments_for(method)
+            .iter()
+            .map(|best| best.map(Duration::from_secs_f64))
+            .sum()
+    }
+
+    /// Lowers the best segment of split `index` to `secs` if it's faster
+    pub fn improve_best_segment(&mut self, index: usize, method: TimingMethod, secs: f64) {
+        let best_segments = match method {
+            TimingMethod::RealTime => &mut self.best_segments,
+            TimingMethod::GameTime => &mut self.game_best_segments,
+        };
+        if let Some(best) = best_segments.get_mut(index) {
+            if best.is_none_or(|best| secs < best) {
+                *best = Some(secs);
+            }
+        }
+    }
+
+    /// Time since the start of the run at which each split was hit on the comparison,
+    /// in seconds. Splits without a time to compare against are `None`.
+    pub fn comparison_split_times(
+        &self,
+        comparison: Comparison,
+        method: TimingMethod,
+    ) -> Vec<Option<f64>> {
+        let segments = match comparison {
+            Comparison::PersonalBest => self
+                .personal_best(method)
+                .map(|pb| pb.split_times_for(method).to_vec())
+                .unwrap_or_default(),
+            Comparison::BestSegments => self
+                .best_segments_for(method)
+                .into_iter()
+                .map_while(|best| best.map(Some))
+                .collect(),
+        };
+        let mut total = 0.0;
+        let mut split_times: Vec<Option<f64>> = segments
+            .iter()
+            .map(|secs| {
+                let secs = (*secs)?;
+                total += secs;
+                Some(total)
+            })
+            .collect();
+        split_times.resize(self.splits.len(), None);
+        split_times
+    }
+
+    pub fn read_from<T: std::io::Read>(reader: &mut T) -> Result<Self, RunDataFileError> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        let rund = RunData::from_bytes(buf)?;
+        Ok(rund)
+    }
+
+    /// Parses any known version of the format. Older versions are upgraded in memory, so
+    /// writing the result back produces a file of the current version.
+    pub fn from_bytes(content: Vec<u8>) -> Result<Self, ParseErr> {
+        // 4 from signature + 1 from version
+        if content.len() < 5 {
+            return Err(ParseErr::InvalidHeaderLength { len: content.len() });
+        }
+        if content[..4] != SIGNATURE {
+            let found = content[..4]
+                .try_into()
+                .expect("Signature should be 4 bytes");
+            return Err(ParseErr::InvalidSignature { found });
+        }
+        let version = content[4];
+        if version >= CHUNKED_VERSION {
+            let mut run = Self::from_chunks(&content, 5)?;
+            // Newer versions can be read as far as the chunks known here go, but there's
+            // no telling what they changed in them. They keep their version so `as_bytes`
+            // refuses to write them over with less than they had.
+            run.version = version.max(VERSION);
+            Ok(run)
+        } else {
+            Self::from_sections(&content, 5, version)
+        }
+    }
+
+    fn from_chunks(content: &[u8], offset: usize) -> Result<Self, ParseErr> {
+        let chunks = read_chunks(content, offset)?;
+        // Each payload is read up to its end, as if it was the rest of the file, so errors
+        // point at the right byte. They can have bytes past what's known here, which newer
+        // versions added.
+        let payload = |tag: [u8; 4]| {
+            chunks
+                .iter()
+                .find(|(chunk_tag, _)| *chunk_tag == tag)
+                .map(|(_, payload)| (&content[..payload.end], payload.start))
+        };
+        let missing = |chunk: &'static str| ParseErr::MissingChunk {
+            chunk,
+            offset: content.len(),
+        };
+
+        let (name, offset) = payload(CHUNK_GAME).ok_or_else(|| missing("GAME"))?;
+        if name.len() == offset {
+            return Err(ParseErr::InvalidRunName { offset });
+        }
+        let name =
+            String::from_utf8(name[offset..].to_vec()).map_err(|_| ParseErr::InvalidUtf8 {
+                section: Section::GameName,
+                offset,
+                item: None,
+            })?;
+        let (splits, mut offset) = payload(CHUNK_SPLITS).ok_or_else(|| missing("SPLT"))?;
+        let splits = read_splits(splits, &mut offset, VERSION)?;
+        let attempts = match payload(CHUNK_ATTEMPTS) {
+            Some((attempts, mut offset)) => {
+                read_attempts(attempts, &mut offset, VERSION, splits.len())?
+            }
+            None => Vec::new(),
+        };
+
+        let mut run = Self::new(name, splits);
+        run.attempts = attempts;
+        if let Some((best_segments, mut offset)) = payload(CHUNK_BEST_SEGMENTS) {
+            run.read_best_segments(best_segments, &mut offset, VERSION)?;
+        }
+        if let Some((metadata, mut offset)) = payload(CHUNK_METADATA) {
+            let game = std::mem::take(&mut run.metadata.game);
+            run.metadata = RunMetadata {
+                game,
+                ..read_metadata(metadata, &mut offset, VERSION)?
+            };
+        }
+        if let Some((groups, mut offset)) = payload(CHUNK_GROUPS) {
+            run.read_groups(groups, &mut offset, VERSION)?;
+        }
+        if let Some((start_offset, mut offset)) = payload(CHUNK_START_OFFSET) {
+            run.start_offset = read_start_offset(start_offset, &mut offset)?;
+        }
+        const KNOWN_CHUNKS: [[u8; 4]; 7] = [
+            CHUNK_GAME,
+            CHUNK_SPLITS,
+            CHUNK_ATTEMPTS,
+            CHUNK_BEST_SEGMENTS,
+            CHUNK_METADATA,
+            CHUNK_GROUPS,
+            CHUNK_START_OFFSET,
+        ];
+        run.unknown_chunks = chunks
+            .into_iter()
+            .filter(|(tag, _)| !KNOWN_CHUNKS.contains(tag))
+            .map(|(tag, payload)| (tag, content[payload].to_vec()))
+            .collect();
+        Ok(run)
+    }
+
+    /// Reads the sections of the versions before chunks, which follow one another in a
+    /// fixed order
+    fn from_sections(content: &[u8], mut offset: usize, version: u8) -> Result<Self, ParseErr> {
+        let name_offset = offset;
+        let name = read_text(
+            content,
+            &mut offset,
+            version,
+            (Section::GameName, None),
+            "the game name",
+        )?;
+        if name.is_empty() {
+            return Err(ParseErr::InvalidRunName {
+                offset: name_offset,
+            });
+        }
+
+        let splits = read_splits(content, &mut offset, version)?;
+        let attempts = read_attempts(content, &mut offset, version, splits.len())?;
+
+        let mut run = Self::new(name, splits);
+        run.attempts = attempts;
+
+        // Best segments got their own chunk on version 3, before that they only come
+        // from the attempts
+        if version >= 3 {
+            run.read_best_segments(content, &mut offset, version)?;
+        }
+
+        // Version 6 added the metadata chunk, before that the name was all there was. It
+        // stays whole as the game: LiveSplit imports wrote it as "Game - Category" but
+        // nothing tells those apart from games with " - " in their title.
+        if version >= 6 {
+            let game = std::mem::take(&mut run.metadata.game);
+            run.metadata = RunMetadata {
+                game,
+                ..read_metadata(content, &mut offset, version)?
+            };
+        }
+
+        // Split groups were introduced on version 7
+        if version >= 7 {
+            run.read_groups(content, &mut offset, version)?;
+        }
+
+        // The start offset was introduced on version 8
+        if version >= 8 {
+            run.start_offset = read_start_offset(content, &mut offset)?;
+        }
+
+        Ok(run)
+    }
+
+    fn read_best_segments(
+        &mut self,
+        content: &[u8],
+        offset: &mut usize,
+        version: u8,
+    ) -> Result<(), ParseErr> {
+        let start = *offset;
+        let chunk_len = read_len(content, offset, version).ok_or_else(|| {
+            ParseErr::invalid(
+                (Section::BestSegments, None),
+                start,
+                "the best segments count",
+                found_at(content, start),
+            )
+        })?;
+        if chunk_len != self.splits.len() {
+            return Err(ParseErr::invalid(
+                (Section::BestSegments, None),
+                start,
+                format!("{} best segments, one per split", self.splits.len()),
+                chunk_len.to_string(),
+            ));
+        }
+        for i in 0..chunk_len {
+            let place = (Section::BestSegments, Some(i));
+            let mut read = |what: &str| {
+                read_number_bytes!(content, *offset, u8).ok_or_else(|| {
+                    ParseErr::invalid(place, *offset, what, found_at(content, *offset))
+                })
+            };
+            let flags = read("the flags")?;
+            let mut read = |what: &str| {
+                read_number_bytes!(content, *offset, f64).ok_or_else(|| {
+                    ParseErr::invalid(place, *offset, what, found_at(content, *offset))
+                })
+            };
+            if flags & BEST_REAL_TIME != 0 {
+                self.best_segments[i] = Some(read("the real time best segment")?);
+            }
+            if flags & BEST_GAME_TIME != 0 {
+                self.game_best_segments[i] = Some(read("the game time best segment")?);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_groups(
+        &mut self,
+        content: &[u8],
+        offset: &mut usize,
+        version: u8,
+    ) -> Result<(), ParseErr> {
+        let start = *offset;
+        let groups_count = read_len(content, offset, version).ok_or_else(|| {
+            ParseErr::invalid(
+                (Section::Groups, None),
+                start,
+                "the groups count",
+                found_at(content, start),
+            )
+        })?;
+        for i in 0..groups_count {
+            let place = (Section::Groups, Some(i));
+            let group_offset = *offset;
+            let name = read_text(content, offset, version, place, "the group name")?;
+            let mut read = |what: &str| {
+                let start = *offset;
+                read_len(content, offset, version)
+                    .ok_or_else(|| ParseErr::invalid(place, start, what, found_at(content, start)))
+            };
+            let start = read("the first split")?;
+            let len = read("the splits count")?;
+            self.add_group(name, start, len).map_err(|_| {
+                ParseErr::invalid(
+                    place,
+                    group_offset,
+                    format!(
+                        "a group within the {} splits that doesn't overlap another",
+                        self.splits.len()
+                    ),
+                    format!("{len} splits from split {start}"),
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn add_attempt(&mut self, split_durations: Vec<Duration>) {
+        let split_durations = split_durations.into_iter().map(Some).collect();
+        self.add_attempt_with_game_time(split_durations, Vec::new());
+    }
+
+    /// Records a completed attempt timed with both methods, skipped splits are `None`.
+    /// `game_split_durations` is empty when game time wasn't tracked.
+    pub fn add_attempt_with_game_time(
+        &mut self,
+        split_durations: Vec<Option<Duration>>,
+        game_split_durations: Vec<Option<Duration>>,
+    ) -> &mut AttemptData {
+        let attempt = AttemptData::new(
+            AttemptOutcome::Completed,
+            sum_split_durations(split_durations),
+            sum_split_durations(game_split_durations),
+        );
+        self.attempts.push(attempt);
+        self.attempts.last_mut().unwrap()
+    }
+
+    /// Records an attempt with totals of its own rather than the sum of its splits, like
+    /// one that ended before the end of the run. Only the splits reached are given, the
+    /// totals are the time on the timers when it ended.
+    pub fn add_unfinished_attempt(
+        &mut self,
+        outcome: AttemptOutcome,
+        split_durations: Vec<Option<Duration>>,
+        game_split_durations: Vec<Option<Duration>>,
+        end_time: Duration,
+        game_end_time: Duration,
+    ) -> &mut AttemptData {
+        let (_, split_times) = sum_split_durations(split_durations);
+        let (_, game_split_times) = sum_split_durations(game_split_durations);
+        let attempt = AttemptData::new(
+            outcome,
+            (end_time, split_times),
+            (game_end_time, game_split_times),
+        );
+        self.attempts.push(attempt);
+        self.attempts.last_mut().unwrap()
+    }
+
+    /// Takes back the last recorded attempt, like when its last split gets undone
+    pub fn pop_attempt(&mut self) -> Option<AttemptData> {
+        self.attempts.pop()
+    }
+
+    pub fn write_to<T: std::io::Write>(&self, writer: &mut T) -> Result<(), RunDataFileError> {
+        match self.as_bytes() {
+            Err(err) => Err(RunDataFileError::SerializeError(err)),
+            Ok(bytes) => {
+                writer.write_all(&bytes)?;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>, SerializeErr> {
+        if self.version > VERSION {
+            return Err(SerializeErr::NewerVersion {
+                version: self.version,
+            });
+        }
+        let mut bytes = SIGNATURE.to_vec();
+        bytes.push(self.version);
+
+        if self.metadata.game.is_empty() {
+            return Err(SerializeErr::MissingGameName);
+        }
+        push_chunk(&mut bytes, CHUNK_GAME, self.metadata.game.as_bytes())?;
+
+        // Add split related things, right now only the names
+        let mut payload = Vec::new();
+        push_varint(&mut payload, self.splits.len() as u64);
+        for split in self.splits.iter() {
+            push_varint(&mut payload, split.len() as u64);
+            push_str_bytes!(payload, split);
+        }
+        push_chunk(&mut bytes, CHUNK_SPLITS, &payload)?;
+
+        let mut payload = Vec::new();
+        self.push_attempts(&mut payload)?;
+        push_chunk(&mut bytes, CHUNK_ATTEMPTS, &payload)?;
+
+        let mut payload = Vec::new();
+        self.push_best_segments(&mut payload);
+        push_chunk(&mut bytes, CHUNK_BEST_SEGMENTS, &payload)?;
+
+        // The game name has its own chunk
+        let mut payload = Vec::new();
+        push_metadata(&mut payload, &self.metadata);
+        push_chunk(&mut bytes, CHUNK_METADATA, &payload)?;
+
+        let mut payload = Vec::new();
+        push_varint(&mut payload, self.groups.len() as u64);
+        for group in self.groups.iter() {
+            push_len_str(&mut payload, &group.name);
+            push_varint(&mut payload, group.start as u64);
+            push_varint(&mut payload, group.len as u64);
+        }
+        push_chunk(&mut bytes, CHUNK_GROUPS, &payload)?;
+
+        push_chunk(
+            &mut bytes,
+            CHUNK_START_OFFSET,
+            &self.start_offset.to_le_bytes(),
+        )?;
+
+        for (tag, payload) in self.unknown_chunks.iter() {
+            push_chunk(&mut bytes, *tag, payload)?;
+        }
+
+        Ok(bytes)
+    }
+
+    /// Adds attempt durations and splits reached
+    fn push_attempts(&self, bytes: &mut Vec<u8>) -> Result<(), SerializeErr> {
+        let splits_count = self.splits.len();
+        push_varint(bytes, self.attempts.len() as u64);
+        for (i, attempt) in self.attempts.iter().enumerate() {
+            push_attempt_times(
+                bytes,
+                attempt.total_duration,
+                &attempt.split_times,
+                splits_count,
+            )
+            .map_err(|found| SerializeErr::TooManySplitTimes {
+                attempt: i,
+                method: TimingMethod::RealTime,
+                splits_count,
+                found,
+            })?;
+            push_attempt_times(
+                bytes,
+                attempt.game_total_duration,
+                &attempt.game_split_times,
+                splits_count,
+            )
+            .map_err(|found| SerializeErr::TooManySplitTimes {
+                attempt: i,
+                method: TimingMethod::GameTime,
+                splits_count,
+                found,
+            })?;
+            push_attempt_info(bytes, attempt);
+        }
+        Ok(())
+    }
+
+    /// Adds best segments, with a flags byte per split saying which timing methods have one
+    fn push_best_segments(&self, bytes: &mut Vec<u8>) {
+        push_varint(bytes, self.splits.len() as u64);
+        let best_segments = self.best_segments_for(TimingMethod::RealTime);
+        let game_best_segments = self.best_segments_for(TimingMethod::GameTime);
+        for (best, game_best) in best_segments.iter().zip(game_best_segments.iter()) {
+            let mut flags = 0u8;
+            if best.is_some() {
+                flags |= BEST_REAL_TIME;
+            }
+            if game_best.is_some() {
+                flags |= BEST_GAME_TIME;
+            }
+            bytes.push(flags);
+            for secs in [best, game_best].into_iter().flatten() {
+                push_number_bytes!(bytes, secs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_splits() {
+        let expected_header: Vec<u8> = vec![
+            // File Signature
+            SIGNATURE[0],
+            SIGNATURE[1],
+            SIGNATURE[2],
+            SIGNATURE[3],
+            // Version Number
+            VERSION,
+        ];
+
+        let expected_game: Vec<u8> = vec![
+            // Chunk Tag
+            b'G', b'A', b'M', b'E', // Payload length as u32: 4
+            0b00000100, 0b00000000, 0b00000000, 0b00000000, // UTF-8 Characters
+            0b01110100, // 't'
+            0b01100101, // 'e'
+            0b01110011, // 's'
+            0b01110100, // 't'
+        ];
+
+        let expected_splits: Vec<u8> = vec![
+            // Splits Count: 3
+            0b00000011, // Split 1
+            // Split Name Length
+            0b00000010, // 2
+            // UTF-8 Characters
+            0b01010011, // 'S'
+            0b00110001, // '1'
+            // Split 2
+            // Split Name Length
+            0b00000010, // 2
+            // UTF-8 Characters
+            0b01010011, // 'S'
+            0b00110010, // '2'
+            // Split 3
+            // Split Name Length
+            0b00000010, // 2
+            // UTF-8 Characters
+            0b01010011, // 'S'
+            0b00110011, // '3'
+        ];
+
+        // TODO: Make it so I don't have to specify the bytes manually for the duration numbers to be able to test for multiple attempts
+        let expected_attempts: Vec<u8> = vec![
+            // Attempts Count
+            0b00000001, // 1
+            // Attempt 1 seconds duration: 9
+            0b00001001, 0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000,
+            0b00000000, // Attempt 1 fractional nanos duration: 700,000,000
+            0b00000000, 0b00100111, 0b10111001, 0b00101001,
+            // Attempt 1 splits used
+            0b00000011, // 3
+            // Attempt 1 Split 1 duration as f64: 3.21
+            0b10101110, 0b01000111, 0b11100001, 0b01111010, 0b00010100, 0b10101110, 0b00001001,
+            0b01000000, // Attempt 1 Split 2 duration as f64: 3.23
+            0b11010111, 0b10100011, 0b01110000, 0b00111101, 0b00001010, 0b11010111, 0b00001001,
+            0b01000000, // Attempt 1 Split 3 duration as f64: 3.26
+            0b00010100, 0b10101110, 0b01000111, 0b11100001, 0b01111010, 0b00010100, 0b00001010,
+            0b01000000, // Attempt 1 game time seconds duration: 0
+            0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000,
+            0b00000000, // Attempt 1 game time fractional nanos duration: 0
+            0b00000000, 0b00000000, 0b00000000, 0b00000000,
+            // Attempt 1 game time splits used
+            0b00000000, // 0
+            0b00000000, // Attempt 1 has no timestamps
+            0b00000000, // Attempt 1 outcome: Completed
+            0b00000000, // Attempt 1 pause count: 0
+            // Attempt 1 paused seconds: 0
+            0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000, 0b00000000,
+            0b00000000, // Attempt 1 paused nanos: 0
+            0b00000000, 0b00000000, 0b00000000, 0b00000000,
+        ];
+
+        // Generate the run data
+        let mut rund = RunData::new(
+            String::from("test"),
+            vec!["S1".to_string(), "S2".to_string(), "S3".to_string()],
+        );
+        rund.add_attempt(vec![
+            Duration::from_secs_f64(3.21),
+            Duration::from_secs_f64(3.23),
+            Duration::from_secs_f64(3.26),
+        ]);
+
+        let result = rund
+            .as_bytes()
+            .expect("Should be able to transform test run data to bytes without problems");
+        let mut res_idx = 0;
+        let res_len = result.len();
+
+        // Test Header section
+        assert!(
+            res_len > expected_header.len(),
+            "Generated header section is too small! Expected {} but got {}",
+            expected_header.len(),
+            res_len - res_idx
+        );
+        let section = &result[res_idx..expected_header.len()];
+        assert_eq!(
+            &expected_header, section,
+            "The generated header section (right) doesn't match with the expected header (left)!"
+        );
+        res_idx += expected_header.len();
+
+        // Test the layout of the game chunk, the rest are checked through their payloads
+        let section = &result[res_idx..(res_idx + expected_game.len())];
+        assert_eq!(
+            &expected_game, section,
+            "The generated game chunk (right) doesn't match with the expected game chunk (left)!"
+        );
+        let crc = crc32(section).to_le_bytes();
+        res_idx += expected_game.len();
+        assert_eq!(&crc, &result[res_idx..(res_idx + 4)], "Game chunk checksum");
+
+        let chunks: Vec<_> = read_chunks(&result, expected_header.len())
+            .expect("Generated chunks should be well formed")
+            .into_iter()
+            .map(|(tag, payload)| (tag, &result[payload]))
+            .collect();
+        let tags: Vec<&[u8; 4]> = chunks.iter().map(|(tag, _)| tag).collect();
+        assert_eq!(
+            vec![b"GAME", b"SPLT", b"ATMP", b"BEST", b"META", b"GRPS", b"OFST"],
+            tags
+        );
+
+        // Test Splits section
+        // TODO> Expand it to how the Attempts section is for easier understanding
+        assert_eq!(
+            &expected_splits, chunks[1].1,
+            "The generated splits section (right) doesn't match with the expected splits (left)!"
+        );
+
+        // Test Attempts section
+        // God save me I spent too much time here because I misstyped 2 bits in the expected data :'v
+        let attempts = chunks[2].1;
+        assert!(
+            attempts.len() >= expected_attempts.len(),
+            "Generated attempts section is too small! Expected {} but got {}",
+            expected_attempts.len(),
+            attempts.len()
+        );
+
+        let got = u8::from_le(attempts[0]);
+        let exp = u8::from_le(expected_attempts[0]);
+        assert_eq!(
+            exp, got,
+            "Attempts count is expected to be {exp} ({exp:#010b}) but got {got} ({got:#010b})"
+        );
+
+        let mut offset = 1usize;
+        let got = u64::from_le_bytes({
+            let v: Vec<u8> = attempts.iter().skip(offset).take(8).copied().collect();
+            let v: [u8; 8] = v.try_into().expect("Should be able to create a [u8; 8]");
+            v
+        });
+        let exp = u64::from_le_bytes({
+            let v: Vec<u8> = expected_attempts
+                .iter()
+                .skip(offset)
+                .take(8)
+                .copied()
+                .collect();
+            let v: [u8; 8] = v.try_into().expect("Should be able to create a [u8; 8]");
+            v
+        });
+        assert_eq!(exp, got, "Attempt total duration seconds expected to be {exp} ({exp:#066b}) but got {got} ({got:#066b})");
+
+        offset += 8;
+        let got = u32::from_le_bytes({
+            let v: Vec<u8> = attempts.iter().skip(offset).take(4).copied().collect();
+            let v: [u8; 4] = v.try_into().expect("Should be able to create a [u8;4]");
+            v
+        });
+        let exp = u32::from_le_bytes({
+            let v: Vec<u8> = expected_attempts
+                .iter()
+                .skip(offset)
+                .take(4)
+                .copied()
+                .collect();
+            let v: [u8; 4] = v.try_into().expect("Should be able to create a [u8; 4]");
+            v
+        });
+        assert_eq!(exp, got, "Attempt total duration nanos subsec expected to be {exp} ({exp:#034b}) but got {got} ({got:#034b})");
+
+        offset += 4;
+        let got = u8::from_le(attempts[offset]);
+        let exp = u8::from_le(expected_attempts[offset]);
+        assert_eq!(exp, got, "Expected attempt's splits used area to be equal to: {exp} ({exp:#010b}) but got {got} ({got:#010b})");
+
+        assert_eq!(
+            &expected_attempts, attempts,
+            "The generated attempts section (right) doesn't match with the expected attempts (left)!"
+        );
+
+        let expected_best_segments: Vec<u8> = vec![
+            // Best Segments Count: 3
+            0b00000011,
+            // Split 1 has a real time best segment of 3.21
+            BEST_REAL_TIME,
+            0b10101110,
+            0b01000111,
+            0b11100001,
+            0b01111010,
+            0b00010100,
+            0b10101110,
+            0b00001001,
+            0b01000000,
+            // Split 2 has a real time best segment of 3.23
+            BEST_REAL_TIME,
+            0b11010111,
+            0b10100011,
+            0b01110000,
+            0b00111101,
+            0b00001010,
+            0b11010111,
+            0b00001001,
+            0b01000000,
+            // Split 3 has a real time best segment of 3.26
+            BEST_REAL_TIME,
+            0b00010100,
+            0b10101110,
+            0b01000111,
+            0b11100001,
+            0b01111010,
+            0b00010100,
+            0b00001010,
+            0b01000000,
+        ];
+        assert_eq!(
+            &expected_best_segments, chunks[3].1,
+            "The generated best segments section (right) doesn't match with the expected best segments (left)!"
+        );
+
+        let expected_metadata: Vec<u8> = vec![
+            0b00000000, // Category length: 0
+            0b00000000, // Platform length: 0
+            0b00000000, // Region length: 0
+            0b00000000, // Metadata flags
+            0b00000000, // Variables count: 0
+        ];
+        assert_eq!(
+            &expected_metadata, chunks[4].1,
+            "The generated metadata section (right) doesn't match with the expected metadata (left)!"
+        );
+        // Groups count: 0
+        assert_eq!(&[0b00000000], chunks[5].1);
+        // Start offset: 0.0
+        assert_eq!(&[0b00000000; 8], chunks[6].1);
+    }
+
+    #[test]
+    fn read_serialized_data() {
+        let exp_run = {
+            let mut rund = RunData::new("test".into(), vec!["S1".into(), "S2".into(), "S3".into()]);
+            rund.add_attempt(vec![
+                Duration::from_secs_f64(3.21),
+                Duration::from_secs_f64(3.23),
+                Duration::from_secs_f64(3.26),
+            ]);
+            rund
+        };
+        let content = exp_run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        // {
+        //     let mut f = std::fs::File::create("foo.bss").unwrap();
+        //     f.write_all(&content).expect("Expected to manage to write all the buffer onto the file");
+        //     f.flush().expect("Expected to be able to flush file after write");
+        // }
+
+        let got_run =
+            RunData::from_bytes(content.clone()).expect("Expected no issues when parsing bytes");
+
+        assert_eq!(
+            exp_run.version, got_run.version,
+            "Expected (left) to read version number {} but read number {}",
+            exp_run.version, got_run.version
+        );
+        assert_eq!(
+            exp_run.metadata, got_run.metadata,
+            "Expected (left) to read name as {:?} but read string {:?}",
+            exp_run.metadata, got_run.metadata
+        );
+
+        let exp_count = exp_run.splits.len();
+        let got_count = got_run.splits.len();
+        assert_eq!(
+            exp_count, got_count,
+            "Expected (left) to find {exp_count} splits but only found {got_count}"
+        );
+        for i in 0..exp_count {
+            let exp_name = &exp_run.splits[i];
+            let got_name = &got_run.splits[i];
+            assert_eq!(
+                exp_name, got_name,
+                "Expected (left) split {i} to be named {exp_name} but it is named {got_name}"
+            );
+        }
+
+        let exp_count = exp_run.attempts.len();
+        let got_count = got_run.attempts.len();
+        assert_eq!(
+            exp_count, got_count,
+            "Expected (left) to find {exp_count} attempts but only found {got_count}"
+        );
+        for i in 0..exp_count {
+            let exp_attempt = &exp_run.attempts[i];
+            let got_attempt = &got_run.attempts[i];
+            assert_eq!(
+		exp_attempt.total_duration,
+		got_attempt.total_duration,
+		"Expected (left) attempt {i} to have a total duration of {:?} but it has a total duration of {:?}",
+		exp_attempt.total_duration,
+		got_attempt.total_duration
+	    );
+            assert_eq!(
+                exp_attempt.split_times, got_attempt.split_times,
+                "Expected (left) attempt {i} to have the specified split times as seconds f64"
+            );
+        }
+    }
+
+    #[test]
+    fn read_version_0_data() {
+        let content: Vec<u8> = vec![
+            // File Signature
+            SIGNATURE[0],
+            SIGNATURE[1],
+            SIGNATURE[2],
+            SIGNATURE[3],
+            // Version Number
+            0b00000000,
+            // Run Name length as a single byte: 4
+            0b00000100,
+            b't',
+            b'e',
+            b's',
+            b't',
+            // Splits Count: 2
+            0b00000010,
+            0b00000010,
+            b'S',
+            b'1',
+            0b00000010,
+            b'S',
+            b'2',
+            // Attempts Count: 1
+            0b00000001,
+            // Attempt 1 seconds duration: 9
+            0b00001001,
+            0b00000000,
+            0b00000000,
+            0b00000000,
+            0b00000000,
+            0b00000000,
+            0b00000000,
+            0b00000000,
+            // Attempt 1 fractional nanos duration: 0
+            0b00000000,
+            0b00000000,
+            0b00000000,
+            0b00000000,
+            // Attempt 1 splits used: 0
+            0b00000000,
+        ];
+
+        let run = RunData::from_bytes(content).expect("Expected to be able to parse version 0");
+        assert_eq!(
+            VERSION, run.version,
+            "Version 0 data should be upgraded in memory"
+        );
+        assert_eq!("test", run.metadata.game);
+        assert_eq!(vec!["S1".to_string(), "S2".to_string()], run.splits);
+        // Attempts that reached no split are kept, the first reader skipped them
+        assert_eq!(1, run.attempts.len());
+        assert_eq!(Duration::from_secs(9), run.attempts[0].total_duration);
+        assert!(run.attempts[0].split_times.is_empty());
+        assert_eq!(AttemptOutcome::Reset, run.attempts[0].outcome);
+        assert_eq!(None, run.attempts[0].started_at);
+    }
+
+    #[test]
+    fn read_serialized_wide_lengths() {
+        let long_name = "a".repeat(300);
+        let splits: Vec<String> = (0..300).map(|i| format!("Split-{i:03}")).collect();
+        let mut exp_run = RunData::new(long_name.clone(), splits.clone());
+        for i in 0..300 {
+            exp_run.add_attempt(vec![Duration::from_millis(i); 260]);
+        }
+
+        let content = exp_run
+            .as_bytes()
+            .expect("Lengths above u8::MAX should be serializable");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+
+        assert_eq!(long_name, got_run.metadata.game);
+        assert_eq!(splits, got_run.splits);
+        assert_eq!(300, got_run.attempts.len());
+        for (i, attempt) in got_run.attempts.iter().enumerate() {
+            assert_eq!(
+                260,
+                attempt.split_times.len(),
+                "Attempt {i} lost split times"
+            );
+        }
+    }
+
+    #[test]
+    fn varint_round_trip() {
+        for value in [0, 1, 127, 128, 255, 300, 16_384, u32::MAX as u64, u64::MAX] {
+            let mut bytes = Vec::new();
+            push_varint(&mut bytes, value);
+            let mut offset = 0;
+            assert_eq!(Some(value), read_varint(&bytes, &mut offset));
+            assert_eq!(bytes.len(), offset, "Varint for {value} left unread bytes");
+        }
+    }
+
+    #[test]
+    fn read_game_time() {
+        let mut exp_run = RunData::new("test".into(), vec!["S1".into(), "S2".into()]);
+        exp_run.add_attempt_with_game_time(
+            vec![Some(Duration::from_secs(10)), Some(Duration::from_secs(20))],
+            vec![Some(Duration::from_secs(8)), Some(Duration::from_secs(15))],
+        );
+        exp_run.add_attempt(vec![Duration::from_secs(12)]);
+        let content = exp_run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+
+        let attempt = &got_run.attempts[0];
+        assert_eq!(Duration::from_secs(30), attempt.total_duration());
+        assert_eq!(Duration::from_secs(23), attempt.game_total_duration());
+        assert_eq!(
+            &[Some(8.0), Some(15.0)],
+            attempt.split_times_for(TimingMethod::GameTime)
+        );
+        let attempt = &got_run.attempts[1];
+        assert_eq!(
+            &[Some(12.0)],
+            attempt.split_times_for(TimingMethod::RealTime)
+        );
+        assert!(attempt.game_split_times().is_empty());
+    }
+
+    #[test]
+    fn personal_best_comparison() {
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into()]);
+        run.add_attempt(vec![Duration::from_secs(10), Duration::from_secs(20)]);
+        // Faster but never finished
+        run.add_attempt(vec![Duration::from_secs(5)]);
+        run.add_attempt(vec![Duration::from_secs(12), Duration::from_secs(15)]);
+        run.add_attempt(vec![Duration::from_secs(9), Duration::from_secs(22)]);
+
+        let pb = run
+            .personal_best(TimingMethod::RealTime)
+            .expect("Expected a personal best out of the finished attempts");
+        assert_eq!(Duration::from_secs(27), pb.total_duration());
+        assert_eq!(
+            vec![Some(12.0), Some(27.0)],
+            run.comparison_split_times(Comparison::PersonalBest, TimingMethod::RealTime)
+        );
+        assert!(run.personal_best(TimingMethod::GameTime).is_none());
+        assert_eq!(
+            vec![None, None],
+            run.comparison_split_times(Comparison::PersonalBest, TimingMethod::GameTime)
+        );
+    }
+
+    #[test]
+    fn best_segments() {
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into(), "S3".into()]);
+        run.add_attempt(vec![Duration::from_secs(10), Duration::from_secs(20)]);
+        run.add_attempt_with_game_time(
+            vec![
+                Some(Duration::from_secs(12)),
+                Some(Duration::from_secs(15)),
+                Some(Duration::from_secs(30)),
+            ],
+            vec![Some(Duration::from_secs(11))],
+        );
+        assert_eq!(
+            vec![Some(10.0), Some(15.0), Some(30.0)],
+            run.best_segments_for(TimingMethod::RealTime)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(55)),
+            run.sum_of_best(TimingMethod::RealTime)
+        );
+        assert_eq!(None, run.sum_of_best(TimingMethod::GameTime));
+        assert_eq!(
+            vec![Some(10.0), Some(25.0), Some(55.0)],
+            run.comparison_split_times(Comparison::BestSegments, TimingMethod::RealTime)
+        );
+
+        let content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let mut got_run =
+            RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+        assert_eq!(
+            run.best_segments_for(TimingMethod::RealTime),
+            got_run.best_segments_for(TimingMethod::RealTime)
+        );
+        assert_eq!(
+            vec![Some(11.0), None, None],
+            got_run.best_segments_for(TimingMethod::GameTime)
+        );
+
+        // Best segments outlive the attempts they came from
+        got_run.attempts.clear();
+        let content = got_run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+        assert_eq!(
+            Some(Duration::from_secs(55)),
+            got_run.sum_of_best(TimingMethod::RealTime)
+        );
+    }
+
+    #[test]
+    fn skipped_splits() {
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into(), "S3".into()]);
+        run.add_attempt_with_game_time(
+            vec![
+                Some(Duration::from_secs(10)),
+                None,
+                Some(Duration::from_secs(12)),
+            ],
+            vec![],
+        );
+        let content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+
+        let attempt = &got_run.attempts[0];
+        assert_eq!(&[Some(10.0), None, Some(12.0)], attempt.split_times());
+        assert_eq!(Duration::from_secs(22), attempt.total_duration());
+        // The segment after a skip spans two splits so it can't be a best
+        assert_eq!(
+            vec![Some(10.0), None, None],
+            got_run.best_segments_for(TimingMethod::RealTime)
+        );
+    }
+
+    #[test]
+    fn reset_attempts() {
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into()]);
+        run.add_attempt(vec![Duration::from_secs(10), Duration::from_secs(20)]);
+        run.add_unfinished_attempt(
+            AttemptOutcome::Reset,
+            vec![Some(Duration::from_secs(9))],
+            vec![],
+            Duration::from_secs(14),
+            Duration::ZERO,
+        );
+        run.add_unfinished_attempt(
+            AttemptOutcome::Reset,
+            vec![],
+            vec![],
+            Duration::from_secs(3),
+            Duration::ZERO,
+        );
+        let content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+
+        assert_eq!(3, got_run.attempts.len());
+        assert_eq!(2, got_run.reset_count());
+        assert_eq!(AttemptOutcome::Completed, got_run.attempts[0].outcome());
+        let attempt = &got_run.attempts[1];
+        assert_eq!(AttemptOutcome::Reset, attempt.outcome());
+        assert_eq!(&[Some(9.0)], attempt.split_times());
+        assert_eq!(Duration::from_secs(14), attempt.total_duration());
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            got_run
+                .personal_best(TimingMethod::RealTime)
+                .map(|pb| pb.total_duration())
+        );
+        assert_eq!(
+            vec![Some(9.0), Some(20.0)],
+            got_run.best_segments_for(TimingMethod::RealTime)
+        );
+    }
+
+    #[test]
+    fn attempt_info() {
+        let started_at = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 250_000_000);
+        let ended_at = started_at + Duration::from_secs(50);
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into()]);
+        let attempt = run.add_unfinished_attempt(
+            AttemptOutcome::Abandoned,
+            vec![Some(Duration::from_secs(9))],
+            vec![],
+            Duration::from_secs(14),
+            Duration::ZERO,
+        );
+        attempt.set_timestamps(Some(started_at), Some(ended_at));
+        attempt.set_pauses(2, Duration::from_millis(36_500));
+        run.add_attempt(vec![Duration::from_secs(10), Duration::from_secs(20)]);
+        let content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+
+        let attempt = &got_run.attempts[0];
+        assert_eq!(AttemptOutcome::Abandoned, attempt.outcome());
+        assert_eq!(Some(started_at), attempt.started_at());
+        assert_eq!(Some(ended_at), attempt.ended_at());
+        assert_eq!(2, attempt.pause_count());
+        assert_eq!(Duration::from_millis(36_500), attempt.paused_duration());
+        assert_eq!(0, got_run.reset_count());
+        let attempt = &got_run.attempts[1];
+        assert_eq!(AttemptOutcome::Completed, attempt.outcome());
+        assert_eq!(None, attempt.started_at());
+        assert_eq!(0, attempt.pause_count());
+    }
+
+    #[test]
+    fn run_metadata() {
+        let mut exp_run = RunData::new("Super Mario 64".into(), vec!["S1".into()]);
+        *exp_run.metadata_mut() = RunMetadata {
+            game: "Super Mario 64".into(),
+            category: "16 Star".into(),
+            platform: "Nintendo 64".into(),
+            region: "JPN".into(),
+            uses_emulator: true,
+            variables: vec![
+                ("Version".into(), "1.0".into()),
+                ("Glitches".into(), "".into()),
+            ],
+        };
+        let content = exp_run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+        assert_eq!(exp_run.metadata, got_run.metadata);
+
+        exp_run.metadata_mut().game.clear();
+        assert_eq!(
+            Err(SerializeErr::MissingGameName),
+            exp_run.as_bytes(),
+            "A run needs a game name"
+        );
+    }
+
+    #[test]
+    fn upgrade_name_to_metadata() {
+        let mut content = SIGNATURE.to_vec();
+        // Version 5 had no metadata chunk
+        content.push(5);
+        content.push(20);
+        content.extend_from_slice(b"Sonic - The Hedgehog");
+        // No splits, attempts nor best segments
+        content.extend_from_slice(&[0, 0, 0]);
+        let run = RunData::from_bytes(content).expect("Expected to be able to parse version 5");
+        assert_eq!("Sonic - The Hedgehog", run.metadata.game);
+        assert_eq!("", run.metadata.category);
+    }
+
+    #[test]
+    fn edit_splits() {
+        let secs = Duration::from_secs;
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into(), "S3".into()]);
+        run.add_attempt(vec![secs(10), secs(20), secs(30)]);
+        run.add_attempt_with_game_time(
+            vec![Some(secs(11)), None, Some(secs(40))],
+            vec![Some(secs(9)), None, Some(secs(35))],
+        );
+        run.add_unfinished_attempt(
+            AttemptOutcome::Reset,
+            vec![Some(secs(12))],
+            vec![],
+            secs(15),
+            Duration::ZERO,
+        );
+
+        assert_eq!(Ok(3), run.add_split("S4".into()));
+        assert_eq!(Ok(1), run.insert_split(1, "S1.5".into()));
+        assert_eq!(
+            Err(EditErr::IndexOutOfRange),
+            run.insert_split(9, "S9".into())
+        );
+        assert_eq!(
+            &[Some(10.0), None, Some(20.0), Some(30.0), None],
+            run.attempts[0].split_times()
+        );
+        assert_eq!(&[Some(12.0)], run.attempts[2].split_times());
+
+        assert_eq!(Some("S4".to_string()), run.remove_split(4));
+        assert_eq!(Some("S1.5".to_string()), run.remove_split(1));
+        assert_eq!(
+            &[Some(10.0), Some(20.0), Some(30.0)],
+            run.attempts[0].split_times()
+        );
+
+        // Removing a split merges its time into the next one
+        assert_eq!(Some("S1".to_string()), run.remove_split(0));
+        assert_eq!(&[Some(30.0), Some(30.0)], run.attempts[0].split_times());
+        assert_eq!(&[None, Some(51.0)], run.attempts[1].split_times());
+        assert_eq!(&[None, Some(44.0)], run.attempts[1].game_split_times());
+        assert!(run.attempts[2].split_times().is_empty());
+        assert_eq!(secs(60), run.attempts[0].total_duration());
+
+        // Or into the previous one when it's the last
+        assert_eq!(Some("S3".to_string()), run.remove_split(1));
+        assert_eq!(&[Some(60.0)], run.attempts[0].split_times());
+        assert_eq!(&[Some(51.0)], run.attempts[1].split_times());
+        assert_eq!(
+            vec![Some(51.0)],
+            run.best_segments_for(TimingMethod::RealTime)
+        );
+        assert_eq!(
+            Some(secs(51)),
+            run.personal_best(TimingMethod::RealTime)
+                .map(|pb| pb.total_duration())
+        );
+    }
+
+    #[test]
+    fn swap_splits() {
+        let secs = Duration::from_secs;
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into(), "S3".into()]);
+        run.add_attempt(vec![secs(10), secs(20), secs(30)]);
+        run.add_unfinished_attempt(
+            AttemptOutcome::Reset,
+            vec![Some(secs(12))],
+            vec![],
+            secs(15),
+            Duration::ZERO,
+        );
+        run.swap_splits(1, 0);
+        run.rename_split(2, "Final".into());
+        assert_eq!(&["S2", "S1", "Final"], run.split_names());
+        assert_eq!(
+            &[Some(20.0), Some(10.0), Some(30.0)],
+            run.attempts[0].split_times()
+        );
+        // It never reached the split that's first now
+        assert!(run.attempts[1].split_times().is_empty());
+        assert_eq!(secs(15), run.attempts[1].total_duration());
+        assert_eq!(AttemptOutcome::Reset, run.attempts[1].outcome());
+    }
+
+    #[test]
+    fn split_groups() {
+        let names = (1..=6).map(|i| format!("S{i}")).collect();
+        let mut run = RunData::new("test".into(), names);
+        assert_eq!(Ok(0), run.add_group("World 2".into(), 3, 3));
+        assert_eq!(Ok(0), run.add_group("World 1".into(), 0, 2));
+        assert_eq!(
+            Err(EditErr::InvalidGroup),
+            run.add_group("Overlap".into(), 1, 3)
+        );
+        assert_eq!(
+            Err(EditErr::InvalidGroup),
+            run.add_group("Too long".into(), 2, 5)
+        );
+        assert_eq!(
+            Err(EditErr::InvalidGroup),
+            run.add_group("Empty".into(), 2, 0)
+        );
+
+        let content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let mut run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+        assert_eq!(2, run.groups().len());
+        assert_eq!("World 1", run.groups()[0].name());
+        assert_eq!(0..2, run.groups()[0].range());
+        assert_eq!(Some("World 2"), run.group_of(4).map(|g| g.name()));
+        assert_eq!(None, run.group_of(2));
+
+        // Groups follow the splits around as they're edited
+        run.insert_split(1, "S1.5".into()).unwrap();
+        run.insert_split(3, "S2.5".into()).unwrap();
+        assert_eq!(0..3, run.groups()[0].range());
+        assert_eq!(5..8, run.groups()[1].range());
+        for _ in 0..3 {
+            run.remove_split(0);
+        }
+        assert_eq!(1, run.groups().len());
+        assert_eq!(2..5, run.groups()[0].range());
+    }
+
+    #[test]
+    fn start_offset() {
+        let mut run = RunData::new("test".into(), vec!["S1".into()]);
+        run.set_start_offset(-1.5);
+        let content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run =
+            RunData::from_bytes(content.clone()).expect("Expected no issues when parsing bytes");
+        assert_eq!(-1.5, got_run.start_offset());
+
+        // Files without the offset start at zero
+        let old = edit_chunks(&content, |tag, payload| {
+            (tag != CHUNK_START_OFFSET).then(|| payload.to_vec())
+        });
+        let old_run = RunData::from_bytes(old).expect("Expected no issues when parsing bytes");
+        assert_eq!(0.0, old_run.start_offset());
+
+        let nan = edit_chunks(&content, |tag, payload| {
+            Some(match tag {
+                CHUNK_START_OFFSET => f64::NAN.to_le_bytes().to_vec(),
+                _ => payload.to_vec(),
+            })
+        });
+        let result = RunData::from_bytes(nan);
+        assert!(
+            matches!(
+                result,
+                Err(ParseErr::InvalidValue {
+                    section: Section::StartOffset,
+                    ..
+                })
+            ),
+            "Expected invalid start offset error but got {result:?}"
+        );
+
+        run.set_start_offset(f64::INFINITY);
+        assert_eq!(0.0, run.start_offset());
+    }
+
+    #[test]
+    fn read_utf8_names() {
+        let exp_run = RunData::new(
+            "ゼルダの伝説 時のオカリナ".into(),
+            vec![
+                "デクの樹サマ".into(),
+                "Split 🌳".into(),
+                "Ganon 🐷⚔️".into(),
+            ],
+        );
+        let content = exp_run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+
+        assert_eq!(exp_run.metadata, got_run.metadata);
+        assert_eq!(exp_run.splits, got_run.splits);
+    }
+
+    #[test]
+    fn reject_invalid_utf8_names() {
+        let content: Vec<u8> = vec![
+            SIGNATURE[0],
+            SIGNATURE[1],
+            SIGNATURE[2],
+            SIGNATURE[3],
+            // Last version before chunks
+            0b00001000,
+            // Run Name length: 2
+            0b00000010,
+            // Lone continuation bytes aren't valid UTF-8
+            0b10000000,
+            0b10111111,
+            // Splits Count: 0
+            0b00000000,
+            // Attempts Count: 0
+            0b00000000,
+        ];
+
+        let result = RunData::from_bytes(content);
+        assert!(
+            matches!(
+                result,
+                Err(ParseErr::InvalidUtf8 {
+                    section: Section::GameName,
+                    offset: 6,
+                    item: None
+                })
+            ),
+            "Expected invalid UTF-8 error but got {result:?}"
+        );
+
+        let content = RunData::new("test".into(), vec!["S1".into()])
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        let content = edit_chunks(&content, |tag, payload| {
+            Some(match tag {
+                CHUNK_GAME => vec![0b10000000, 0b10111111],
+                _ => payload.to_vec(),
+            })
+        });
+        let result = RunData::from_bytes(content);
+        assert!(
+            matches!(
+                result,
+                Err(ParseErr::InvalidUtf8 {
+                    section: Section::GameName,
+                    offset: 13,
+                    item: None
+                })
+            ),
+            "Expected invalid UTF-8 error but got {result:?}"
+        );
+    }
+
+    /// Rebuilds a file with the payloads `edit` gives for each chunk, dropping the chunks
+    /// it gives `None` for
+    fn edit_chunks(content: &[u8], edit: impl Fn([u8; 4], &[u8]) -> Option<Vec<u8>>) -> Vec<u8> {
+        let mut edited = content[..5].to_vec();
+        for (tag, payload) in read_chunks(content, 5).expect("Expected well formed chunks") {
+            if let Some(payload) = edit(tag, &content[payload]) {
+                push_chunk(&mut edited, tag, &payload).unwrap();
+            }
+        }
+        edited
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(0, crc32(b""));
+        assert_eq!(0xCBF43926, crc32(b"123456789"));
+    }
+
+    #[test]
+    fn keep_unknown_chunks() {
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into()]);
+        run.add_attempt(vec![Duration::from_secs(10), Duration::from_secs(20)]);
+        run.set_start_offset(2.0);
+        let mut content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        // A chunk this version doesn't know is written back as it was
+        push_chunk(&mut content, *b"NEW!", b"Something from the future").unwrap();
+        let got_run =
+            RunData::from_bytes(content.clone()).expect("Expected no issues when parsing bytes");
+        assert_eq!(
+            vec![(*b"NEW!", b"Something from the future".to_vec())],
+            got_run.unknown_chunks
+        );
+        assert_eq!(content, got_run.as_bytes().unwrap());
+
+        // A newer version with more in a known chunk
+        content[4] = VERSION + 1;
+        let content = edit_chunks(&content, |tag, payload| {
+            let mut payload = payload.to_vec();
+            if tag == CHUNK_METADATA {
+                payload.push(0xFF);
+            }
+            Some(payload)
+        });
+
+        let got_run = RunData::from_bytes(content).expect("Expected no issues when parsing bytes");
+        assert_eq!(run.metadata, got_run.metadata);
+        assert_eq!(run.splits, got_run.splits);
+        assert_eq!(1, got_run.attempts.len());
+        assert_eq!(2.0, got_run.start_offset());
+        // It can't be written without losing the byte added to the metadata
+        assert_eq!(
+            Err(SerializeErr::NewerVersion {
+                version: VERSION + 1
+            }),
+            got_run.as_bytes()
+        );
+    }
+
+    #[test]
+    fn detect_corrupted_chunks() {
+        let content = RunData::new("test".into(), vec!["S1".into(), "S2".into()])
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        // The splits chunk comes after the header and the game chunk holding "test"
+        let splits_offset = 5 + 4 + 4 + 4 + 4;
+        assert_eq!(b"SPLT", &content[splits_offset..splits_offset + 4]);
+
+        let mut corrupted = content.clone();
+        corrupted[splits_offset + 10] ^= 0b00000100;
+        let result = RunData::from_bytes(corrupted);
+        assert!(
+            matches!(&result, Err(ParseErr::ChecksumMismatch { chunk, offset, .. }) if chunk == "SPLT" && *offset == splits_offset),
+            "Expected a checksum mismatch on the splits chunk but got {result:?}"
+        );
+
+        // The start offset chunk is the last one
+        let offset_chunk = content.len() - (4 + 4 + 8 + 4);
+        let result = RunData::from_bytes(content[..content.len() - 2].to_vec());
+        assert!(
+            matches!(&result, Err(ParseErr::TruncatedChunk { chunk, offset, expected: 20, found: 18 }) if chunk == "OFST" && *offset == offset_chunk),
+            "Expected the start offset chunk to be truncated but got {result:?}"
+        );
+
+        let missing = edit_chunks(&content, |tag, payload| {
+            (tag != CHUNK_SPLITS).then(|| payload.to_vec())
+        });
+        let result = RunData::from_bytes(missing);
+        assert!(
+            matches!(result, Err(ParseErr::MissingChunk { chunk: "SPLT", .. })),
+            "Expected the splits chunk to be missing but got {result:?}"
+        );
+    }
+
+    #[test]
+    fn parse_error_details() {
+        let mut run = RunData::new("test".into(), vec!["S1".into(), "S2".into()]);
+        run.add_attempt(vec![Duration::from_secs(10), Duration::from_secs(20)]);
+        let content = run
+            .as_bytes()
+            .expect("Expected to be able to create bytes from test run data struct");
+        // Leave the run with a single split while the attempt has two
+        let content = edit_chunks(&content, |tag, payload| {
+            Some(match tag {
+                CHUNK_SPLITS => vec![1, 2, b'S', b'1'],
+                _ => payload.to_vec(),
+            })
+        });
+        let (_, attempts) = read_chunks(&content, 5)
+            .unwrap()
+            .into_iter()
+            .find(|(tag, _)| *tag == CHUNK_ATTEMPTS)
+            .unwrap();
+        // After the attempts count and the total duration
+        let splits_used_offset = attempts.start + 1 + 12;
+        let err = RunData::from_bytes(content).expect_err("Expected the attempt to be invalid");
+        assert!(
+            matches!(
+                &err,
+                ParseErr::InvalidValue {
+                    section: Section::Attempts,
+                    item: Some(0),
+                    found,
+                    ..
+                } if found == "2"
+            ),
+            "Expected too many split times on attempt 0 but got {err:?}"
+        );
+        assert_eq!(splits_used_offset, err.offset());
+        assert_eq!(
+            format!("Invalid attempts (attempt 0) at byte {splits_used_offset}, expected at most 1 Real Time split times but found 2"),
+            err.to_string()
+        );
+
+        // A version 5 file cut before its best segments
+        let mut content = SIGNATURE.to_vec();
+        content.push(5);
+        content.push(10);
+        content.extend_from_slice(b"SM64 - 70%");
+        content.extend_from_slice(&[0, 0]);
+        let err = RunData::from_bytes(content).expect_err("Expected the file to be cut short");
+        assert_eq!(
+            "Invalid best segments at byte 18, expected the best segments count but found the end of the data",
+            err.to_string()
+        );
+
+        let err = RunData::read_from(&mut &b"nope!"[..]).expect_err("Expected no signature");
+        assert_eq!(
+            r#"Couldn't read the run file: Not a run file, expected the signature [62, 73, 73, 45] but found [6e, 6f, 70, 65]"#,
+            err.to_string()
+        );
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
@@ -0,0 +1,705 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+#[inline]
+fn zero_dur() -> Duration {
+    Duration::from_nanos(0)
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimingMethod {
+    RealTime,
+    /// Real time minus whatever was spent with game time paused, like loads
+    GameTime,
+}
+impl TimingMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimingMethod::RealTime => "Real Time",
+            TimingMethod::GameTime => "Game Time",
+        }
+    }
+}
+
+/// A time that can be below zero, like a stopwatch still counting down its start offset
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timestamp {
+    negative: bool,
+    seconds: u64,
+    subsecs: u32,
+}
+impl Timestamp {
+    /// Negative seconds give a negative timestamp, anything that isn't finite is zero
+    pub fn from_secs_f64(secs: f64) -> Self {
+        let duration = Duration::try_from_secs_f64(secs.abs()).unwrap_or_default();
+        Self {
+            negative: secs < 0.0 && !duration.is_zero(),
+            ..Self::from(duration)
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// How far from zero the timestamp is, whichever side it's on
+    pub fn duration(&self) -> Duration {
+        Duration::new(self.seconds, self.subsecs)
+    }
+
+    pub fn seconds(&self) -> f64 {
+        let seconds = self.duration().as_secs_f64();
+        if self.negative {
+            -seconds
+        } else {
+            seconds
+        }
+    }
+
+    pub fn expanded(&self) -> ExpandedTimestamp {
+        ExpandedTimestamp {
+            negative: self.negative,
+            ..ExpandedTimestamp::from(self.duration())
+        }
+    }
+}
+/// Negative timestamps are zero as a `Duration`
+impl From<Timestamp> for Duration {
+    fn from(timestamp: Timestamp) -> Self {
+        if timestamp.negative {
+            Duration::ZERO
+        } else {
+            timestamp.duration()
+        }
+    }
+}
+impl From<Duration> for Timestamp {
+    fn from(duration: Duration) -> Self {
+        Self {
+            negative: false,
+            seconds: duration.as_secs(),
+            subsecs: duration.subsec_nanos(),
+        }
+    }
+}
+
+pub struct ExpandedTimestamp {
+    pub negative: bool,
+    pub hours: u64,
+    pub minutes: u64,
+    pub seconds: u64,
+    pub milliseconds: u32,
+}
+impl ExpandedTimestamp {
+    pub fn simple_text(&self) -> String {
+        let sign = if self.negative { "-" } else { "" };
+        if self.hours > 0 {
+            format!(
+                "{sign}{:02}:{:02}:{:02}",
+                self.hours, self.minutes, self.seconds
+            )
+        } else {
+            format!("{sign}{:02}:{:02}", self.minutes, self.seconds)
+        }
+    }
+    pub fn millis_text(&self) -> String {
+        format!("{:03}", self.milliseconds)
+    }
+}
+
+impl std::fmt::Display for ExpandedTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{}{:02}:{:02}:{:02}.{:03}",
+            if self.negative { "-" } else { "" },
+            self.hours,
+            self.minutes,
+            self.seconds,
+            self.milliseconds
+        )
+    }
+}
+impl From<Duration> for ExpandedTimestamp {
+    fn from(time: Duration) -> Self {
+        let total_secs = time.as_secs();
+        let millis = time.subsec_millis();
+        let mins = total_secs / 60;
+        let hours = mins / 60;
+        let mins = mins % 60;
+        let secs = total_secs % 60;
+        Self {
+            negative: false,
+            hours,
+            minutes: mins,
+            seconds: secs,
+            milliseconds: millis,
+        }
+    }
+}
+
+/// Where stopwatches get the current time from
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// The actual time, through `Instant::now`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock that only moves when it's told to, for tests. Clones share the same time.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Rc<Cell<Duration>>,
+}
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Rc::new(Cell::new(Duration::ZERO)),
+        }
+    }
+
+    pub fn advance(&self, time: Duration) {
+        self.elapsed.set(self.elapsed.get() + time);
+    }
+}
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+}
+
+#[derive(Default)]
+pub struct Stopwatch<C: Clock = SystemClock> {
+    clock: C,
+    start_time: Option<Instant>,
+    elapsed: Duration,
+    /// Time spent below zero after starting, before `time_elapsed` starts counting
+    countdown: Duration,
+}
+
+impl Stopwatch {
+    pub fn start_new() -> Self {
+        let mut sw = Self::new();
+        sw.start();
+        sw
+    }
+
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+}
+impl<C: Clock> Stopwatch<C> {
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            start_time: None,
+            elapsed: zero_dur(),
+            countdown: zero_dur(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.start_time.is_some()
+    }
+
+    /// Whether the stopwatch hasn't run since it was cleared
+    pub fn is_cleared(&self) -> bool {
+        !self.is_running() && self.elapsed.is_zero()
+    }
+
+    /// Time since the stopwatch started, counting the time spent below zero
+    fn raw_elapsed(&self) -> Duration {
+        self.elapsed
+            + match self.start_time {
+                None => Duration::ZERO,
+                Some(x) => self.clock.now().saturating_duration_since(x),
+            }
+    }
+
+    /// Time since the stopwatch got past zero, still zero while counting down
+    pub fn time_elapsed(&self) -> Duration {
+        self.raw_elapsed().saturating_sub(self.countdown)
+    }
+
+    /// The time on the stopwatch, negative while counting down
+    pub fn timestamp(&self) -> Timestamp {
+        let raw_elapsed = self.raw_elapsed();
+        if raw_elapsed < self.countdown {
+            Timestamp {
+                negative: true,
+                ..Timestamp::from(self.countdown - raw_elapsed)
+            }
+        } else {
+            Timestamp::from(raw_elapsed - self.countdown)
+        }
+    }
+
+    pub fn countdown(&self) -> Duration {
+        self.countdown
+    }
+
+    /// Makes the stopwatch start at `-countdown` instead of zero, it's kept through
+    /// `clear`
+    pub fn set_countdown(&mut self, countdown: Duration) {
+        self.countdown = countdown;
+    }
+
+    pub fn start(&mut self) {
+        if self.start_time.is_none() {
+            self.start_time = Some(self.clock.now());
+        }
+    }
+
+    pub fn pause(&mut self) -> Duration {
+        if self.start_time.is_some() {
+            self.elapsed = self.raw_elapsed();
+            self.start_time = None;
+        }
+        self.time_elapsed()
+    }
+
+    pub fn toggle(&mut self) -> bool {
+        if self.is_running() {
+            let _ = self.pause();
+            false
+        } else {
+            self.start();
+            true
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.start_time = None;
+        self.elapsed = zero_dur();
+    }
+
+    /// Jumps to `time` as if that much had elapsed, it keeps running if it was
+    pub fn set_time(&mut self, time: Duration) {
+        self.set_timestamp(Timestamp::from(time));
+    }
+
+    /// Like `set_time` but the time can be negative, back into the countdown
+    pub fn set_timestamp(&mut self, timestamp: Timestamp) {
+        self.elapsed = if timestamp.negative {
+            self.countdown.saturating_sub(timestamp.duration())
+        } else {
+            self.countdown + timestamp.duration()
+        };
+        if self.start_time.is_some() {
+            self.start_time = Some(self.clock.now());
+        }
+    }
+
+    /// Folds the time since the stopwatch was started into the elapsed time, returning
+    /// the total
+    pub fn update_start_time(&mut self) -> Duration {
+        if self.start_time.is_some() {
+            self.elapsed = self.raw_elapsed();
+            self.start_time = Some(self.clock.now());
+        }
+        self.time_elapsed()
+    }
+}
+#[derive(Default)]
+pub struct StopSplit {
+    split_start: Option<Duration>,
+    elapsed: Duration,
+    completed: bool,
+    skipped: bool,
+}
+impl StopSplit {
+    pub fn new() -> Self {
+        Self {
+            split_start: None,
+            elapsed: zero_dur(),
+            completed: false,
+            skipped: false,
+        }
+    }
+
+    pub fn new_started<C: Clock>(sw: &Stopwatch<C>) -> Self {
+        let split_start = sw.time_elapsed();
+        Self {
+            split_start: Some(split_start),
+            elapsed: zero_dur(),
+            completed: false,
+            skipped: false,
+        }
+    }
+
+    pub fn not_started(&self) -> bool {
+        self.split_start.is_none()
+    }
+
+    pub fn is_done(&self) -> bool {
+        !self.not_started() && self.completed
+    }
+
+    pub fn is_skipped(&self) -> bool {
+        self.is_done() && self.skipped
+    }
+
+    pub fn time_elapsed<C: Clock>(&self, sw: &Stopwatch<C>) -> Duration {
+        // Not started split just returns zero
+        if self.not_started() {
+            return zero_dur();
+        }
+        if self.completed {
+            return self.elapsed;
+        }
+        // The stopwatch can be set back before the split started, like game time
+        sw.time_elapsed().saturating_sub(self.split_start.unwrap())
+    }
+
+    pub fn start<C: Clock>(&mut self, sw: &Stopwatch<C>) {
+        if !self.not_started() {
+            return;
+        }
+        if self.is_done() {
+            return;
+        }
+        self.elapsed = zero_dur();
+        self.split_start = Some(sw.time_elapsed());
+    }
+
+    pub fn start_at_zero(&mut self) {
+        if !self.not_started() {
+            return;
+        }
+        if self.is_done() {
+            return;
+        }
+        self.elapsed = zero_dur();
+        self.split_start = Some(zero_dur());
+    }
+
+    /// Starts the split as if it had been started at `split_start` on the stopwatch
+    pub fn start_at(&mut self, split_start: Duration) {
+        if !self.not_started() {
+            return;
+        }
+        self.elapsed = zero_dur();
+        self.split_start = Some(split_start);
+    }
+
+    /// Finishes the split without a time. Returns when the split started so the next one
+    /// can take over the time spent on it.
+    pub fn skip(&mut self) -> Option<Duration> {
+        if self.not_started() {
+            return None;
+        }
+        self.elapsed = zero_dur();
+        self.completed = true;
+        self.skipped = true;
+        self.split_start
+    }
+
+    pub fn stop<C: Clock>(&mut self, sw: &Stopwatch<C>) {
+        if !self.not_started() {
+            let elapsed = sw.time_elapsed().saturating_sub(self.split_start.unwrap());
+            self.elapsed = elapsed;
+            self.completed = true;
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if !self.not_started() {
+            self.completed = false;
+            self.skipped = false;
+        }
+    }
+
+    pub fn toggle_split<C: Clock>(&mut self, sw: &Stopwatch<C>) {
+        if self.not_started() {
+            self.start(sw);
+        } else if self.completed {
+            self.resume();
+        } else {
+            self.stop(sw);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.split_start = None;
+        self.elapsed = zero_dur();
+        self.completed = false;
+        self.skipped = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    fn manual_stopwatch() -> (ManualClock, Stopwatch<ManualClock>) {
+        let clock = ManualClock::new();
+        (clock.clone(), Stopwatch::with_clock(clock))
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let (clock, mut sw) = manual_stopwatch();
+        clock.advance(secs(3));
+        assert!(!sw.is_running());
+        assert_eq!(Duration::ZERO, sw.time_elapsed());
+
+        sw.start();
+        clock.advance(secs(5));
+        assert_eq!(secs(5), sw.time_elapsed());
+        assert_eq!(secs(5), sw.pause());
+        clock.advance(secs(10));
+        assert_eq!(secs(5), sw.time_elapsed());
+        // Pausing again changes nothing
+        assert_eq!(secs(5), sw.pause());
+
+        sw.start();
+        clock.advance(secs(2));
+        assert_eq!(secs(7), sw.time_elapsed());
+    }
+
+    #[test]
+    fn start_twice_keeps_running() {
+        let (clock, mut sw) = manual_stopwatch();
+        sw.start();
+        clock.advance(secs(4));
+        sw.start();
+        clock.advance(secs(1));
+        assert_eq!(secs(5), sw.time_elapsed());
+    }
+
+    #[test]
+    fn toggle_and_clear() {
+        let (clock, mut sw) = manual_stopwatch();
+        assert!(sw.toggle());
+        clock.advance(secs(2));
+        assert!(!sw.toggle());
+        clock.advance(secs(2));
+        assert_eq!(secs(2), sw.time_elapsed());
+        assert!(sw.toggle());
+
+        sw.clear();
+        assert!(!sw.is_running());
+        clock.advance(secs(2));
+        assert_eq!(Duration::ZERO, sw.time_elapsed());
+    }
+
+    #[test]
+    fn set_time() {
+        let (clock, mut sw) = manual_stopwatch();
+        sw.set_time(secs(30));
+        assert!(!sw.is_running());
+        assert_eq!(secs(30), sw.time_elapsed());
+
+        sw.start();
+        clock.advance(secs(5));
+        sw.set_time(secs(10));
+        assert!(sw.is_running());
+        clock.advance(secs(1));
+        assert_eq!(secs(11), sw.time_elapsed());
+    }
+
+    #[test]
+    fn update_start_time() {
+        let (clock, mut sw) = manual_stopwatch();
+        // Nothing to fold while stopped
+        assert_eq!(Duration::ZERO, sw.update_start_time());
+
+        sw.start();
+        clock.advance(secs(4));
+        assert_eq!(secs(4), sw.update_start_time());
+        assert_eq!(secs(4), sw.time_elapsed());
+        clock.advance(secs(3));
+        assert_eq!(secs(7), sw.update_start_time());
+
+        sw.pause();
+        clock.advance(secs(3));
+        assert_eq!(secs(7), sw.update_start_time());
+        assert!(!sw.is_running());
+    }
+
+    #[test]
+    fn split_times() {
+        let (clock, mut sw) = manual_stopwatch();
+        let mut split = StopSplit::new();
+        assert!(split.not_started());
+        assert_eq!(Duration::ZERO, split.time_elapsed(&sw));
+        // Stopping a split that never started does nothing
+        split.stop(&sw);
+        assert!(!split.is_done());
+
+        sw.start();
+        clock.advance(secs(2));
+        split.start(&sw);
+        clock.advance(secs(3));
+        assert_eq!(secs(3), split.time_elapsed(&sw));
+        // Starting it again doesn't move its start
+        split.start(&sw);
+        assert_eq!(secs(3), split.time_elapsed(&sw));
+
+        split.stop(&sw);
+        assert!(split.is_done());
+        clock.advance(secs(5));
+        assert_eq!(secs(3), split.time_elapsed(&sw));
+
+        // Resuming counts from where the split started, time in between included
+        split.resume();
+        assert!(!split.is_done());
+        assert_eq!(secs(8), split.time_elapsed(&sw));
+
+        split.clear();
+        assert!(split.not_started());
+        assert_eq!(Duration::ZERO, split.time_elapsed(&sw));
+    }
+
+    #[test]
+    fn split_follows_paused_stopwatch() {
+        let (clock, mut sw) = manual_stopwatch();
+        sw.start();
+        let mut split = StopSplit::new_started(&sw);
+        clock.advance(secs(2));
+        sw.pause();
+        clock.advance(secs(10));
+        assert_eq!(secs(2), split.time_elapsed(&sw));
+        sw.start();
+        clock.advance(secs(1));
+        split.stop(&sw);
+        assert_eq!(secs(3), split.time_elapsed(&sw));
+    }
+
+    #[test]
+    fn split_before_its_start() {
+        let (clock, mut sw) = manual_stopwatch();
+        sw.start();
+        clock.advance(secs(10));
+        let mut split = StopSplit::new_started(&sw);
+        // Like game time being set back by a load remover
+        sw.set_time(secs(4));
+        assert_eq!(Duration::ZERO, split.time_elapsed(&sw));
+        split.stop(&sw);
+        assert_eq!(Duration::ZERO, split.time_elapsed(&sw));
+    }
+
+    #[test]
+    fn toggle_split() {
+        let (clock, mut sw) = manual_stopwatch();
+        sw.start();
+        let mut split = StopSplit::new();
+
+        split.toggle_split(&sw);
+        assert!(!split.not_started());
+        assert!(!split.is_done());
+        clock.advance(secs(2));
+
+        split.toggle_split(&sw);
+        assert!(split.is_done());
+        assert_eq!(secs(2), split.time_elapsed(&sw));
+        clock.advance(secs(1));
+
+        split.toggle_split(&sw);
+        assert!(!split.is_done());
+        assert_eq!(secs(3), split.time_elapsed(&sw));
+
+        // A skipped split toggles back to running without being skipped
+        split.skip();
+        assert!(split.is_skipped());
+        split.toggle_split(&sw);
+        assert!(!split.is_done());
+        assert!(!split.is_skipped());
+    }
+
+    #[test]
+    fn skip_split() {
+        let (clock, mut sw) = manual_stopwatch();
+        let mut split = StopSplit::new();
+        assert_eq!(None, split.skip());
+        assert!(!split.is_skipped());
+
+        sw.start();
+        clock.advance(secs(4));
+        split.start(&sw);
+        clock.advance(secs(2));
+        assert_eq!(Some(secs(4)), split.skip());
+        assert!(split.is_skipped());
+        assert_eq!(Duration::ZERO, split.time_elapsed(&sw));
+
+        // The next split takes over from where the skipped one started
+        let mut next = StopSplit::new();
+        next.start_at(secs(4));
+        assert_eq!(secs(2), next.time_elapsed(&sw));
+        let mut first = StopSplit::new();
+        first.start_at_zero();
+        assert_eq!(secs(6), first.time_elapsed(&sw));
+    }
+
+    #[test]
+    fn countdown() {
+        let (clock, mut sw) = manual_stopwatch();
+        sw.set_countdown(Duration::from_millis(1500));
+        assert!(sw.is_cleared());
+        sw.start();
+        let mut split = StopSplit::new();
+        split.start_at_zero();
+        clock.advance(secs(1));
+        assert_eq!(Duration::ZERO, sw.time_elapsed());
+        assert_eq!(-0.5, sw.timestamp().seconds());
+        assert_eq!(Duration::ZERO, split.time_elapsed(&sw));
+
+        sw.pause();
+        assert!(!sw.is_cleared());
+        clock.advance(secs(5));
+        assert_eq!(-0.5, sw.timestamp().seconds());
+        sw.start();
+        clock.advance(secs(2));
+        assert_eq!(Duration::from_millis(1500), sw.time_elapsed());
+        assert!(!sw.timestamp().is_negative());
+        assert_eq!(Duration::from_millis(1500), split.time_elapsed(&sw));
+
+        // Times set from outside are past the countdown
+        sw.set_time(secs(10));
+        assert_eq!(secs(10), sw.time_elapsed());
+
+        // The countdown comes back after a clear
+        sw.clear();
+        sw.start();
+        assert_eq!(-1.5, sw.timestamp().seconds());
+    }
+
+    #[test]
+    fn negative_timestamp() {
+        let timestamp = Timestamp::from_secs_f64(-61.25);
+        assert!(timestamp.is_negative());
+        assert_eq!(Duration::from_millis(61_250), timestamp.duration());
+        assert_eq!(Duration::ZERO, Duration::from(timestamp));
+        let expanded = timestamp.expanded();
+        assert_eq!("-01:01", expanded.simple_text());
+        assert_eq!("250", expanded.millis_text());
+        assert_eq!("-00:01:01.250", expanded.to_string());
+
+        assert!(!Timestamp::from_secs_f64(-0.0).is_negative());
+        assert_eq!(0.0, Timestamp::from_secs_f64(f64::NAN).seconds());
+    }
+
+    #[test]
+    fn real_clock() {
+        let sw = Stopwatch::start_new();
+        assert!(sw.is_running());
+        assert!(SystemClock.now() >= sw.start_time.unwrap());
+    }
+}
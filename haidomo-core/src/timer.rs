@@ -0,0 +1,811 @@
+use crate::action::Action;
+use crate::splits_file::{AttemptOutcome, Comparison, RunData};
+use crate::stopwatch::{Clock, StopSplit, Stopwatch, SystemClock, Timestamp, TimingMethod};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Where the run is at, named like the LiveSplit timer phases
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimerPhase {
+    NotRunning,
+    Running,
+    Paused,
+    Ended,
+}
+impl TimerPhase {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimerPhase::NotRunning => "NotRunning",
+            TimerPhase::Running => "Running",
+            TimerPhase::Paused => "Paused",
+            TimerPhase::Ended => "Ended",
+        }
+    }
+}
+
+/// What a call on the `Timer` did to it, for frontends to react to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    Start,
+    Split,
+    Skip,
+    Undo,
+    Pause,
+    Resume,
+    Reset,
+    Finish,
+}
+impl EventKind {
+    pub const ALL: [EventKind; 8] = [
+        EventKind::Start,
+        EventKind::Split,
+        EventKind::Skip,
+        EventKind::Undo,
+        EventKind::Pause,
+        EventKind::Resume,
+        EventKind::Reset,
+        EventKind::Finish,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Start => "start",
+            EventKind::Split => "split",
+            EventKind::Skip => "skip",
+            EventKind::Undo => "undo",
+            EventKind::Pause => "pause",
+            EventKind::Resume => "resume",
+            EventKind::Reset => "reset",
+            EventKind::Finish => "finish",
+        }
+    }
+}
+
+/// Seconds ahead (negative) or behind (positive) the comparison, like `-1.25` or `+1:02.50`
+pub fn format_delta(delta: f64) -> String {
+    let sign = if delta < 0.0 { '-' } else { '+' };
+    let delta = delta.abs();
+    if delta >= 60.0 {
+        let minutes = (delta / 60.0).floor();
+        format!("{sign}{minutes}:{:05.2}", delta - minutes * 60.0)
+    } else {
+        format!("{sign}{delta:.2}")
+    }
+}
+
+/// Runs through the splits of a `RunData` on real and game time, recording the attempts
+/// in it. Calls that don't apply in the current phase do nothing and return `None`.
+pub struct Timer<C: Clock + Clone = SystemClock> {
+    clock: C,
+    stopwatch: Stopwatch<C>,
+    /// Game time runs alongside `stopwatch` but can be paused on its own, e.g. on loads
+    game_stopwatch: Stopwatch<C>,
+    game_time_paused: bool,
+    primary_method: TimingMethod,
+    comparison: Comparison,
+    /// Comparison split times and best segments as they were when the run started, so
+    /// the attempt recorded when finishing isn't compared against itself
+    comparison_times: Vec<Option<f64>>,
+    best_segments: Vec<Option<f64>>,
+    /// Index of the split name, then the split on real time and on game time
+    splits: Vec<(usize, StopSplit, StopSplit)>,
+    run_data: RunData,
+    at: usize,
+    /// When the current run started and the times it was paused, recorded with the attempt
+    started_at: Option<SystemTime>,
+    pause_count: u32,
+    paused_since: Option<Instant>,
+    paused_duration: Duration,
+    /// Set when an attempt got recorded or taken back, until `take_attempts_changed`
+    attempts_changed: bool,
+}
+impl Timer {
+    pub fn new(run_data: RunData) -> Self {
+        Self::with_clock(run_data, SystemClock)
+    }
+}
+impl<C: Clock + Clone> Timer<C> {
+    pub fn with_clock(run_data: RunData, clock: C) -> Self {
+        let mut timer = Self {
+            stopwatch: Stopwatch::with_clock(clock.clone()),
+            game_stopwatch: Stopwatch::with_clock(clock.clone()),
+            clock,
+            game_time_paused: false,
+            primary_method: TimingMethod::RealTime,
+            comparison: Comparison::PersonalBest,
+            comparison_times: Vec::new(),
+            best_segments: Vec::new(),
+            splits: Self::new_splits(&run_data),
+            run_data,
+            at: 0,
+            started_at: None,
+            pause_count: 0,
+            paused_since: None,
+            paused_duration: Duration::ZERO,
+            attempts_changed: false,
+        };
+        timer.refresh_comparison();
+        timer
+    }
+
+    fn new_splits(run_data: &RunData) -> Vec<(usize, StopSplit, StopSplit)> {
+        run_data
+            .get_indexed_split_names()
+            .iter()
+            .map(|(idx, _)| (*idx, StopSplit::new(), StopSplit::new()))
+            .collect()
+    }
+
+    /// Swaps in another run with the timer back at zero. The run in progress is dropped
+    /// without being recorded, `abandon` it first to keep it.
+    pub fn load_run(&mut self, run_data: RunData) {
+        self.run_data = run_data;
+        self.splits_edited();
+    }
+
+    pub fn run_data(&self) -> &RunData {
+        &self.run_data
+    }
+
+    /// Splits added, removed or moved through here have to be followed by `splits_edited`
+    pub fn run_data_mut(&mut self) -> &mut RunData {
+        &mut self.run_data
+    }
+
+    /// Takes the splits back to the run data after it got edited. Clears the timer, as the
+    /// splits in progress would point to the wrong names.
+    pub fn splits_edited(&mut self) {
+        self.stopwatch.clear();
+        self.game_stopwatch.clear();
+        self.at = 0;
+        self.splits = Self::new_splits(&self.run_data);
+        self.refresh_comparison();
+    }
+
+    /// Whether attempts were recorded or taken back since the last call, i.e. whether
+    /// the run data needs saving
+    pub fn take_attempts_changed(&mut self) -> bool {
+        std::mem::take(&mut self.attempts_changed)
+    }
+
+    /// Index of the split name, then the split on real time and on game time
+    pub fn splits(&self) -> &[(usize, StopSplit, StopSplit)] {
+        &self.splits
+    }
+
+    /// Index of the running split, the split count once the run finished
+    pub fn current_split(&self) -> usize {
+        self.at
+    }
+
+    pub fn current_split_name(&self) -> Option<&String> {
+        let (idx, _, _) = self.splits.get(self.at)?;
+        self.run_data.get_split_name(*idx)
+    }
+
+    pub fn best_segments(&self) -> &[Option<f64>] {
+        &self.best_segments
+    }
+
+    pub fn primary_method(&self) -> TimingMethod {
+        self.primary_method
+    }
+
+    pub fn set_primary_method(&mut self, method: TimingMethod) {
+        self.primary_method = method;
+        self.refresh_comparison();
+    }
+
+    pub fn comparison(&self) -> Comparison {
+        self.comparison
+    }
+
+    pub fn set_comparison(&mut self, comparison: Comparison) {
+        self.comparison = comparison;
+        self.refresh_comparison();
+    }
+
+    pub fn cycle_comparison(&mut self, forward: bool) {
+        let count = Comparison::ALL.len();
+        let at = Comparison::ALL
+            .iter()
+            .position(|c| *c == self.comparison)
+            .unwrap_or_default();
+        let next = if forward { at + 1 } else { at + count - 1 };
+        self.set_comparison(Comparison::ALL[next % count]);
+    }
+
+    fn refresh_comparison(&mut self) {
+        self.comparison_times = self
+            .run_data
+            .comparison_split_times(self.comparison, self.primary_method);
+        self.best_segments = self
+            .run_data
+            .best_segments_for(self.primary_method)
+            .to_vec();
+    }
+
+    pub fn stopwatch_for(&self, method: TimingMethod) -> &Stopwatch<C> {
+        match method {
+            TimingMethod::RealTime => &self.stopwatch,
+            TimingMethod::GameTime => &self.game_stopwatch,
+        }
+    }
+
+    /// Time on the primary timing method, the start offset of the run before it starts
+    pub fn timestamp(&self) -> Timestamp {
+        if !self.is_started() {
+            return Timestamp::from_secs_f64(self.run_data.start_offset());
+        }
+        self.stopwatch_for(self.primary_method).timestamp()
+    }
+
+    pub fn is_started(&self) -> bool {
+        !self.stopwatch.is_cleared()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_started() && self.at >= self.splits.len()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.stopwatch.is_running()
+    }
+
+    pub fn is_in_progress(&self) -> bool {
+        self.is_started() && !self.is_finished()
+    }
+
+    pub fn is_game_time_paused(&self) -> bool {
+        self.game_time_paused
+    }
+
+    pub fn phase(&self) -> TimerPhase {
+        if !self.is_started() {
+            TimerPhase::NotRunning
+        } else if self.is_finished() {
+            TimerPhase::Ended
+        } else if self.is_running() {
+            TimerPhase::Running
+        } else {
+            TimerPhase::Paused
+        }
+    }
+
+    /// Starts a new run from the start offset of the run, whatever the timer was doing.
+    /// A negative offset counts down to zero before the first split starts timing.
+    pub fn start(&mut self) -> Option<EventKind> {
+        self.refresh_comparison();
+        let offset = self.run_data.start_offset();
+        let offset_time = Duration::try_from_secs_f64(offset.abs()).unwrap_or_default();
+        let (countdown, head_start) = if offset < 0.0 {
+            (offset_time, Duration::ZERO)
+        } else {
+            (Duration::ZERO, offset_time)
+        };
+        for sw in [&mut self.stopwatch, &mut self.game_stopwatch] {
+            sw.clear();
+            sw.set_countdown(countdown);
+            if !head_start.is_zero() {
+                sw.set_time(head_start);
+            }
+        }
+        self.game_time_paused = false;
+        self.at = 0;
+        for s in self.splits.iter_mut() {
+            s.1.clear();
+            s.2.clear();
+        }
+        self.started_at = Some(SystemTime::now());
+        self.pause_count = 0;
+        self.paused_since = None;
+        self.paused_duration = Duration::ZERO;
+        self.stopwatch.start();
+        self.game_stopwatch.start();
+        if !self.splits.is_empty() {
+            let s = &mut self.splits[0];
+            s.1.start_at_zero();
+            s.2.start_at_zero();
+        }
+        Some(EventKind::Start)
+    }
+
+    fn stop(&mut self) {
+        self.stopwatch.pause();
+        self.game_stopwatch.pause();
+        for s in self.splits.iter_mut() {
+            if !s.1.is_done() {
+                s.1.stop(&self.stopwatch);
+            }
+            if !s.2.is_done() {
+                s.2.stop(&self.game_stopwatch);
+            }
+        }
+    }
+
+    /// Pauses or resumes both timing methods, game time stays paused if it was on its own
+    pub fn toggle_pause(&mut self) -> Option<EventKind> {
+        if !self.is_in_progress() {
+            return None;
+        }
+        let running = self.stopwatch.toggle();
+        if running && !self.game_time_paused {
+            self.game_stopwatch.start();
+        } else {
+            self.game_stopwatch.pause();
+        }
+        if running {
+            if let Some(paused_since) = self.paused_since.take() {
+                self.paused_duration += self.clock.now().saturating_duration_since(paused_since);
+            }
+            Some(EventKind::Resume)
+        } else {
+            self.pause_count += 1;
+            self.paused_since = Some(self.clock.now());
+            Some(EventKind::Pause)
+        }
+    }
+
+    /// Time the current run has spent paused, including the pause it's on
+    pub fn paused_total(&self) -> Duration {
+        self.paused_duration
+            + self
+                .paused_since
+                .map(|since| self.clock.now().saturating_duration_since(since))
+                .unwrap_or_default()
+    }
+
+    /// Pauses or resumes game time alone. Returns whether game time is paused now.
+    pub fn toggle_game_time_pause(&mut self) -> bool {
+        self.game_time_paused = !self.game_time_paused;
+        if self.game_time_paused {
+            self.game_stopwatch.pause();
+        } else if self.stopwatch.is_running() {
+            self.game_stopwatch.start();
+        }
+        self.game_time_paused
+    }
+
+    pub fn set_game_time(&mut self, time: Duration) {
+        self.game_stopwatch.set_time(time);
+    }
+
+    /// Puts both timing methods at the given times, for playing back a journal
+    pub(crate) fn set_times(&mut self, time: Timestamp, game_time: Timestamp) {
+        self.stopwatch.set_timestamp(time);
+        self.game_stopwatch.set_timestamp(game_time);
+    }
+
+    /// Wall-clock time the run in progress or the last one started, `None` before any
+    pub fn started_at(&self) -> Option<SystemTime> {
+        self.started_at
+    }
+
+    /// Puts back when the run started and how long it was paused, for playing back a
+    /// journal. A pause the run is still on counts from now.
+    pub(crate) fn restore_run_times(&mut self, started_at: SystemTime, paused_duration: Duration) {
+        self.started_at = Some(started_at);
+        self.paused_duration = paused_duration;
+        if self.paused_since.is_some() {
+            self.paused_since = Some(self.clock.now());
+        }
+    }
+
+    /// Finishes the current split, or the run when it's the last one
+    pub fn split(&mut self) -> Option<EventKind> {
+        if !self.is_in_progress() {
+            return None;
+        }
+        self.at += 1;
+        if self.at >= self.splits.len() {
+            self.stop();
+            self.record_attempt();
+            return Some(EventKind::Finish);
+        }
+
+        let prev = self.splits.get_mut(self.at - 1).unwrap();
+        prev.1.stop(&self.stopwatch);
+        prev.2.stop(&self.game_stopwatch);
+        let next = self.splits.get_mut(self.at).unwrap();
+        next.1.start(&self.stopwatch);
+        next.2.start(&self.game_stopwatch);
+        Some(EventKind::Split)
+    }
+
+    /// Moves on to the next split leaving the current one without a time, the next split
+    /// takes over the time spent on it. The last split can't be skipped.
+    pub fn skip_split(&mut self) -> Option<EventKind> {
+        if !self.is_started() || self.at + 1 >= self.splits.len() {
+            return None;
+        }
+        let skipped = self.splits.get_mut(self.at).unwrap();
+        let split_start = skipped.1.skip();
+        let game_split_start = skipped.2.skip();
+        self.at += 1;
+        let next = self.splits.get_mut(self.at).unwrap();
+        if let Some(split_start) = split_start {
+            next.1.start_at(split_start);
+        }
+        if let Some(game_split_start) = game_split_start {
+            next.2.start_at(game_split_start);
+        }
+        Some(EventKind::Skip)
+    }
+
+    /// Reopens the previous split. Undoing the last split takes back the attempt recorded
+    /// when the run finished.
+    pub fn undo_split(&mut self) -> Option<EventKind> {
+        if !self.is_started() || self.at == 0 {
+            return None;
+        }
+        if self.is_finished() {
+            self.run_data.pop_attempt();
+            self.attempts_changed = true;
+            self.stopwatch.start();
+            if !self.game_time_paused {
+                self.game_stopwatch.start();
+            }
+        } else {
+            let current = self.splits.get_mut(self.at).unwrap();
+            current.1.clear();
+            current.2.clear();
+        }
+        self.at -= 1;
+        let prev = self.splits.get_mut(self.at).unwrap();
+        prev.1.resume();
+        prev.2.resume();
+        Some(EventKind::Undo)
+    }
+
+    /// Stops the run and takes the timer back to zero. A run that didn't finish gets
+    /// recorded as a reset attempt with the splits it reached.
+    pub fn reset(&mut self) -> Option<EventKind> {
+        if !self.is_started() {
+            return None;
+        }
+        if !self.is_finished() {
+            self.record_unfinished_attempt(AttemptOutcome::Reset);
+        }
+        self.stopwatch.clear();
+        self.game_stopwatch.clear();
+        self.game_time_paused = false;
+        self.at = 0;
+        for s in self.splits.iter_mut() {
+            s.1.clear();
+            s.2.clear();
+        }
+        Some(EventKind::Reset)
+    }
+
+    /// Records the run in progress as abandoned, for when it's left without a reset.
+    /// Returns whether there was one.
+    pub fn abandon(&mut self) -> bool {
+        if !self.is_in_progress() {
+            return false;
+        }
+        self.record_unfinished_attempt(AttemptOutcome::Abandoned);
+        true
+    }
+
+    /// Does what `action` stands for, starting a new run when splitting or pausing without one
+    pub fn run_action(&mut self, action: Action) -> Option<EventKind> {
+        match action {
+            Action::StartOrSplit => {
+                if self.is_in_progress() {
+                    self.split()
+                } else {
+                    self.start()
+                }
+            }
+            Action::Pause if !self.is_started() => self.start(),
+            Action::Pause => self.toggle_pause(),
+            Action::ToggleGameTime => {
+                if self.is_started() {
+                    self.toggle_game_time_pause();
+                }
+                None
+            }
+            Action::Reset => self.reset(),
+            Action::Undo => self.undo_split(),
+            Action::Skip => self.skip_split(),
+            Action::NextComparison => {
+                self.cycle_comparison(true);
+                None
+            }
+            Action::PrevComparison => {
+                self.cycle_comparison(false);
+                None
+            }
+        }
+    }
+
+    /// Time on each split reached so far for both timing methods, skipped splits are `None`
+    fn reached_split_durations(&self) -> (Vec<Option<Duration>>, Vec<Option<Duration>>) {
+        let split_time = |split: &StopSplit, sw: &Stopwatch<C>| {
+            (!split.is_skipped()).then(|| split.time_elapsed(sw))
+        };
+        let reached = self
+            .splits
+            .iter()
+            .take_while(|(_, split, _)| split.is_done());
+        let split_durations = reached
+            .clone()
+            .map(|(_, split, _)| split_time(split, &self.stopwatch))
+            .collect();
+        let game_split_durations = reached
+            .map(|(_, _, split)| split_time(split, &self.game_stopwatch))
+            .collect();
+        (split_durations, game_split_durations)
+    }
+
+    fn record_attempt(&mut self) {
+        let (split_durations, game_split_durations) = self.reached_split_durations();
+        let paused_total = self.paused_total();
+        let attempt = self
+            .run_data
+            .add_attempt_with_game_time(split_durations, game_split_durations);
+        attempt.set_timestamps(self.started_at, Some(SystemTime::now()));
+        attempt.set_pauses(self.pause_count, paused_total);
+        self.attempts_changed = true;
+    }
+
+    /// Records the run in progress as an attempt that didn't reach the end
+    fn record_unfinished_attempt(&mut self, outcome: AttemptOutcome) {
+        let (split_durations, game_split_durations) = self.reached_split_durations();
+        let paused_total = self.paused_total();
+        let attempt = self.run_data.add_unfinished_attempt(
+            outcome,
+            split_durations,
+            game_split_durations,
+            self.stopwatch.time_elapsed(),
+            self.game_stopwatch.time_elapsed(),
+        );
+        attempt.set_timestamps(self.started_at, Some(SystemTime::now()));
+        attempt.set_pauses(self.pause_count, paused_total);
+        self.attempts_changed = true;
+    }
+
+    /// Delta of every split against the comparison along with the delta of the split
+    /// before it, the running split only has one once it's falling behind
+    pub fn split_deltas(&self) -> Vec<(Option<f64>, f64)> {
+        let sw = self.stopwatch_for(self.primary_method);
+        let mut deltas = Vec::with_capacity(self.splits.len());
+        let mut split_time = 0.0;
+        let mut prev_delta = 0.0;
+        for (i, s) in self.splits.iter().enumerate() {
+            let data = match self.primary_method {
+                TimingMethod::RealTime => &s.1,
+                TimingMethod::GameTime => &s.2,
+            };
+            split_time += data.time_elapsed(sw).as_secs_f64();
+            let split_comparison = self.comparison_times.get(i).copied().flatten();
+            let delta = split_comparison
+                .filter(|_| !data.is_skipped())
+                .and_then(|cmp| {
+                    let delta = split_time - cmp;
+                    let live = i == self.at && sw.is_running() && delta > 0.0;
+                    (data.is_done() || live).then_some(delta)
+                });
+            deltas.push((delta, prev_delta));
+            if let (Some(delta), true) = (delta, data.is_done()) {
+                prev_delta = delta;
+            }
+        }
+        deltas
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::stopwatch::ManualClock;
+
+    /// Timer on a run of three splits and no history, shared with the journal tests
+    pub(crate) fn three_splits<C: Clock + Clone>(clock: C) -> Timer<C> {
+        let names = ["One", "Two", "Three"].map(String::from).to_vec();
+        Timer::with_clock(RunData::new(String::from("Game"), names), clock)
+    }
+
+    #[test]
+    fn run_through_phases() {
+        let mut timer = three_splits(SystemClock);
+        assert_eq!(TimerPhase::NotRunning, timer.phase());
+        assert_eq!(None, timer.split());
+        assert_eq!(None, timer.toggle_pause());
+
+        assert_eq!(Some(EventKind::Start), timer.start());
+        assert_eq!(TimerPhase::Running, timer.phase());
+        assert_eq!(Some(EventKind::Pause), timer.toggle_pause());
+        assert_eq!(TimerPhase::Paused, timer.phase());
+        assert_eq!(Some(EventKind::Resume), timer.toggle_pause());
+
+        assert_eq!(Some(EventKind::Split), timer.split());
+        assert_eq!(Some(EventKind::Skip), timer.skip_split());
+        assert_eq!(Some(&String::from("Three")), timer.current_split_name());
+        // The last split can't be skipped
+        assert_eq!(None, timer.skip_split());
+        assert!(!timer.take_attempts_changed());
+
+        assert_eq!(Some(EventKind::Finish), timer.split());
+        assert_eq!(TimerPhase::Ended, timer.phase());
+        assert!(timer.take_attempts_changed());
+        assert!(!timer.take_attempts_changed());
+        assert_eq!(1, timer.run_data().attempts().len());
+        assert!(timer.splits()[1].1.is_skipped());
+        assert_eq!(None, timer.split());
+    }
+
+    #[test]
+    fn undo_finished_run() {
+        let mut timer = three_splits(SystemClock);
+        timer.start();
+        for _ in 0..3 {
+            timer.split();
+        }
+        assert_eq!(1, timer.run_data().attempts().len());
+        timer.take_attempts_changed();
+
+        assert_eq!(Some(EventKind::Undo), timer.undo_split());
+        assert_eq!(TimerPhase::Running, timer.phase());
+        assert_eq!(2, timer.current_split());
+        assert_eq!(0, timer.run_data().attempts().len());
+        assert!(timer.take_attempts_changed());
+    }
+
+    #[test]
+    fn reset_and_abandon() {
+        let mut timer = three_splits(SystemClock);
+        assert_eq!(None, timer.reset());
+        assert!(!timer.abandon());
+
+        timer.start();
+        timer.split();
+        assert_eq!(Some(EventKind::Reset), timer.reset());
+        assert_eq!(TimerPhase::NotRunning, timer.phase());
+        assert_eq!(1, timer.run_data().reset_count());
+
+        timer.start();
+        assert!(timer.abandon());
+        let attempt = timer.run_data().attempts().last().unwrap();
+        assert_eq!(AttemptOutcome::Abandoned, attempt.outcome());
+
+        // Finished runs aren't recorded again
+        timer.start();
+        for _ in 0..3 {
+            timer.split();
+        }
+        assert!(!timer.abandon());
+        assert_eq!(Some(EventKind::Reset), timer.reset());
+        assert_eq!(3, timer.run_data().attempts().len());
+    }
+
+    #[test]
+    fn run_actions() {
+        let mut timer = three_splits(SystemClock);
+        assert_eq!(None, timer.run_action(Action::ToggleGameTime));
+        assert!(!timer.is_game_time_paused());
+        // Pausing without a run starts one, then pauses it
+        assert_eq!(Some(EventKind::Start), timer.run_action(Action::Pause));
+        assert_eq!(Some(EventKind::Pause), timer.run_action(Action::Pause));
+        assert_eq!(Some(EventKind::Resume), timer.run_action(Action::Pause));
+        assert_eq!(Some(EventKind::Reset), timer.run_action(Action::Reset));
+        assert_eq!(
+            Some(EventKind::Start),
+            timer.run_action(Action::StartOrSplit)
+        );
+        assert_eq!(
+            Some(EventKind::Split),
+            timer.run_action(Action::StartOrSplit)
+        );
+        assert_eq!(Some(EventKind::Undo), timer.run_action(Action::Undo));
+        assert_eq!(None, timer.run_action(Action::NextComparison));
+        assert_ne!(Comparison::PersonalBest, timer.comparison());
+        timer.run_action(Action::PrevComparison);
+        assert_eq!(Comparison::PersonalBest, timer.comparison());
+        for _ in 0..3 {
+            timer.run_action(Action::StartOrSplit);
+        }
+        assert_eq!(TimerPhase::Ended, timer.phase());
+        // Splitting once the run ended starts the next one
+        assert_eq!(
+            Some(EventKind::Start),
+            timer.run_action(Action::StartOrSplit)
+        );
+    }
+
+    #[test]
+    fn delta_format() {
+        assert_eq!("-1.25", format_delta(-1.25));
+        assert_eq!("+0.00", format_delta(0.0));
+        assert_eq!("+1:02.50", format_delta(62.5));
+    }
+
+    #[test]
+    fn game_time_pause() {
+        let mut timer = three_splits(SystemClock);
+        timer.start();
+        assert!(timer.toggle_game_time_pause());
+        assert!(!timer.stopwatch_for(TimingMethod::GameTime).is_running());
+        // Resuming the timer keeps game time paused on its own
+        timer.toggle_pause();
+        timer.toggle_pause();
+        assert!(timer.is_running());
+        assert!(!timer.stopwatch_for(TimingMethod::GameTime).is_running());
+        assert!(!timer.toggle_game_time_pause());
+        assert!(timer.stopwatch_for(TimingMethod::GameTime).is_running());
+
+        timer.set_game_time(Duration::from_secs(90));
+        assert!(
+            timer.stopwatch_for(TimingMethod::GameTime).time_elapsed() >= Duration::from_secs(90)
+        );
+    }
+
+    #[test]
+    fn manual_clock_run() {
+        let clock = ManualClock::new();
+        let names = ["One", "Two"].map(String::from).to_vec();
+        let mut timer = Timer::with_clock(RunData::new(String::from("Game"), names), clock.clone());
+        let secs = Duration::from_secs;
+
+        timer.start();
+        clock.advance(secs(10));
+        assert_eq!(Some(EventKind::Split), timer.split());
+        clock.advance(secs(5));
+        assert_eq!(Some(EventKind::Pause), timer.toggle_pause());
+        clock.advance(secs(30));
+        assert_eq!(secs(30), timer.paused_total());
+        assert_eq!(Some(EventKind::Resume), timer.toggle_pause());
+        clock.advance(secs(5));
+        assert_eq!(Some(EventKind::Finish), timer.split());
+        clock.advance(secs(60));
+
+        let split_times: Vec<_> = timer
+            .splits()
+            .iter()
+            .map(|(_, split, _)| split.time_elapsed(timer.stopwatch_for(TimingMethod::RealTime)))
+            .collect();
+        assert_eq!(vec![secs(10), secs(10)], split_times);
+        assert_eq!(
+            secs(20),
+            timer.stopwatch_for(TimingMethod::RealTime).time_elapsed()
+        );
+
+        let attempt = timer.run_data().attempts().last().unwrap();
+        assert_eq!(secs(20), attempt.total_duration());
+        assert_eq!(&[Some(10.0), Some(10.0)], attempt.split_times());
+        assert_eq!(1, attempt.pause_count());
+        assert_eq!(secs(30), attempt.paused_duration());
+    }
+
+    #[test]
+    fn start_offset() {
+        let clock = ManualClock::new();
+        let mut run = RunData::new(String::from("Game"), vec![String::from("One")]);
+        run.set_start_offset(-1.5);
+        let mut timer = Timer::with_clock(run, clock.clone());
+        assert_eq!(-1.5, timer.timestamp().seconds());
+        assert_eq!(TimerPhase::NotRunning, timer.phase());
+
+        timer.start();
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(-0.5, timer.timestamp().seconds());
+        // Pausing in the countdown keeps the run going
+        timer.toggle_pause();
+        assert_eq!(TimerPhase::Paused, timer.phase());
+        timer.toggle_pause();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(2.5, timer.timestamp().seconds());
+        assert_eq!(Some(EventKind::Finish), timer.split());
+        assert_eq!(&[Some(2.5)], timer.run_data().attempts()[0].split_times());
+
+        timer.run_data_mut().set_start_offset(2.0);
+        timer.start();
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(3.0, timer.timestamp().seconds());
+        assert_eq!(
+            Duration::from_secs(3),
+            timer.splits()[0]
+                .1
+                .time_elapsed(timer.stopwatch_for(TimingMethod::RealTime))
+        );
+    }
+}
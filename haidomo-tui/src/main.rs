@@ -0,0 +1,721 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
+use haidomo_core::action::Action;
+use haidomo_core::journal::{self, Journal, JournalEntry, JournalKind};
+use haidomo_core::run_file::{default_run_data, read_run_file, save_path_for, save_run_file};
+use haidomo_core::settings::{Key, KeyBinding, Settings};
+use haidomo_core::splits_file::RunData;
+use haidomo_core::stopwatch::{ExpandedTimestamp, TimingMethod};
+use haidomo_core::timer::{format_delta, EventKind, Timer, TimerPhase};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait on a key press before drawing the timer again
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+/// Columns taken by the delta and the time after each split name
+const TIMES_WIDTH: usize = 22;
+
+/// Rows of the big timer digits, `#` is a filled cell
+const BIG_DIGITS: [[&str; 5]; 10] = [
+    ["###", "# #", "# #", "# #", "###"],
+    ["  #", "  #", "  #", "  #", "  #"],
+    ["###", "  #", "###", "#  ", "###"],
+    ["###", "  #", "###", "  #", "###"],
+    ["# #", "# #", "###", "  #", "  #"],
+    ["###", "#  ", "###", "  #", "###"],
+    ["###", "#  ", "###", "# #", "###"],
+    ["###", "  #", "  #", "  #", "  #"],
+    ["###", "# #", "###", "# #", "###"],
+    ["###", "# #", "###", "  #", "###"],
+];
+const BIG_COLON: [&str; 5] = [" ", "#", " ", "#", " "];
+const BIG_MINUS: [&str; 5] = ["  ", "  ", "##", "  ", "  "];
+
+/// Piece of a line drawn in one color, the terminal's own when `None`
+type Span = (String, Option<Color>);
+type Line = Vec<Span>;
+
+fn main() {
+    let file_path = std::env::args_os().nth(1).map(PathBuf::from);
+    let run_data = match &file_path {
+        Some(path) if path.exists() => match read_run_file(path) {
+            Ok(run_data) => run_data,
+            Err(err) => {
+                eprintln!("[ERROR] Failed to load {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        },
+        _ => default_run_data(),
+    };
+    let mut app = TuiApp::new(run_data, file_path, Settings::load());
+    app.ask_unfinished_run();
+    if let Err(err) = app.run() {
+        eprintln!("[ERROR] Terminal failed: {err}");
+    }
+    if app.timer.abandon() {
+        app.timer.take_attempts_changed();
+        app.save_file();
+        println!("[INFO] Recorded abandoned attempt");
+    }
+    app.clear_journal();
+    println!("[INFO] {}", app.status);
+}
+
+/// Puts the terminal in raw mode on the alternate screen, until it's dropped
+struct TerminalGuard;
+impl TerminalGuard {
+    fn enter() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(
+            std::io::stdout(),
+            terminal::EnterAlternateScreen,
+            cursor::Hide
+        )?;
+        Ok(Self)
+    }
+}
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(
+            std::io::stdout(),
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+struct TuiApp {
+    timer: Timer,
+    file_path: Option<PathBuf>,
+    /// Journal of the run in progress, next to the splits file
+    journal: Option<Journal>,
+    /// Keybindings and backups, from the same settings file as the window
+    settings: Settings,
+    /// Last thing that happened outside the timer, shown under it
+    status: String,
+    quit: bool,
+}
+
+impl TuiApp {
+    fn new(run_data: RunData, file_path: Option<PathBuf>, settings: Settings) -> Self {
+        let mut status = match settings.binding(Action::StartOrSplit) {
+            Some(binding) => format!("Press {binding} to start, Q to quit"),
+            None => String::from("Press Q to quit"),
+        };
+        // Imported runs get saved as .bss next to the LiveSplit file, unless that'd
+        // write over another run
+        let file_path = file_path.and_then(|path| {
+            let save_path = save_path_for(&path);
+            if save_path.is_none() {
+                let bss_path = path.with_extension("bss");
+                status = format!("{} exists, attempts won't be saved", bss_path.display());
+            }
+            save_path
+        });
+        Self {
+            timer: Timer::new(run_data),
+            journal: file_path.as_deref().map(Journal::for_run),
+            file_path,
+            settings,
+            status,
+            quit: false,
+        }
+    }
+
+    /// Asks before going into the timer what to do with a run the journal had left
+    /// unfinished, like when the timer crashed mid-run
+    fn ask_unfinished_run(&mut self) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        let entries = match journal.unfinished_of(self.timer.run_data()) {
+            Ok(Some(entries)) => entries,
+            Ok(None) => return,
+            Err(err) => {
+                eprintln!("[ERROR] Failed to read {}: {err}", journal.path().display());
+                return;
+            }
+        };
+        print!("The timer closed with a run in progress. Restore it, or record it as a reset? [R/reset] ");
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().read_line(&mut answer);
+        let record_as_reset = answer.trim().eq_ignore_ascii_case("reset");
+        self.restore_run(&entries, record_as_reset);
+    }
+
+    /// Plays the unfinished run from the journal back on the timer, to go on with it or
+    /// to record it as a reset attempt
+    fn restore_run(&mut self, entries: &[JournalEntry], record_as_reset: bool) {
+        if !journal::replay(&mut self.timer, entries) {
+            return;
+        }
+        // Anything recorded and taken back while playing it is already in the file
+        self.timer.take_attempts_changed();
+        if record_as_reset {
+            let event = self.timer.reset();
+            self.timer_changed(event);
+            self.clear_journal();
+        } else {
+            self.status = String::from("Restored the unfinished run");
+        }
+    }
+
+    fn write_journal(&mut self, kind: JournalKind) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        if let Err(err) = journal.record(kind, &self.timer) {
+            self.status = format!("Failed to write to {}: {err}", journal.path().display());
+        }
+    }
+
+    /// Removes the journal once its run is over
+    fn clear_journal(&mut self) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        if let Err(err) = journal.clear() {
+            self.status = format!("Failed to remove {}: {err}", journal.path().display());
+        }
+    }
+
+    fn run(&mut self) -> std::io::Result<()> {
+        let _guard = TerminalGuard::enter()?;
+        let mut stdout = std::io::stdout();
+        while !self.quit {
+            let (width, height) = terminal::size()?;
+            self.draw(&mut stdout, width as usize, height as usize)?;
+            if !event::poll(FRAME_INTERVAL)? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if ctrl => self.quit = true,
+            KeyCode::Char('s') if ctrl => self.save_file(),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('m') => {
+                let method = match self.timer.primary_method() {
+                    TimingMethod::RealTime => TimingMethod::GameTime,
+                    TimingMethod::GameTime => TimingMethod::RealTime,
+                };
+                self.timer.set_primary_method(method);
+            }
+            _ => {
+                if let Some(action) = action_for(&self.settings, &key) {
+                    let event = self.timer.run_action(action);
+                    if action == Action::ToggleGameTime && self.timer.is_started() {
+                        self.write_journal(if self.timer.is_game_time_paused() {
+                            JournalKind::GameTimePause
+                        } else {
+                            JournalKind::GameTimeResume
+                        });
+                    }
+                    self.timer_changed(event);
+                }
+            }
+        }
+    }
+
+    /// Journals what happened and saves the splits when the run history changed, same
+    /// as the window does
+    fn timer_changed(&mut self, event: Option<EventKind>) {
+        let Some(kind) = event else {
+            return;
+        };
+        // Saved before the journal says the run is over, same as the window
+        if self.timer.take_attempts_changed() && self.file_path.is_some() {
+            self.save_file();
+        }
+        self.write_journal(kind.into());
+    }
+
+    fn save_file(&mut self) {
+        let Some(path) = &self.file_path else {
+            self.status = String::from("No file to save to, pass one when starting");
+            return;
+        };
+        self.status = match save_run_file(path, self.timer.run_data(), self.settings.backup_count) {
+            Ok(()) => format!("Saved splits to {}", path.display()),
+            Err(err) => format!("Failed to save {}: {err}", path.display()),
+        };
+    }
+
+    fn draw(&self, out: &mut impl Write, width: usize, height: usize) -> std::io::Result<()> {
+        let lines = self.lines(width);
+        for (row, line) in lines.iter().take(height).enumerate() {
+            queue!(out, cursor::MoveTo(0, row as u16))?;
+            for (text, color) in line {
+                match color {
+                    Some(color) => {
+                        queue!(out, SetForegroundColor(*color), Print(text), ResetColor)?
+                    }
+                    None => queue!(out, Print(text))?,
+                }
+            }
+            queue!(out, terminal::Clear(terminal::ClearType::UntilNewLine))?;
+        }
+        queue!(
+            out,
+            cursor::MoveTo(0, lines.len().min(height) as u16),
+            terminal::Clear(terminal::ClearType::FromCursorDown)
+        )?;
+        out.flush()
+    }
+
+    /// Everything on screen, top to bottom
+    fn lines(&self, width: usize) -> Vec<Line> {
+        let timer = &self.timer;
+        let run_data = timer.run_data();
+        let metadata = run_data.metadata();
+        let mut lines = Vec::new();
+        lines.push(vec![(centered(&metadata.game, width), None)]);
+        let mut category = metadata.category.clone();
+        let variables: Vec<&str> = metadata
+            .variables
+            .iter()
+            .map(|(_, value)| value.as_str())
+            .filter(|value| !value.is_empty())
+            .collect();
+        if !variables.is_empty() {
+            category.push_str(&format!(" ({})", variables.join(", ")));
+        }
+        lines.push(vec![(centered(&category, width), None)]);
+        lines.push(vec![("-".repeat(width), Some(Color::DarkGrey))]);
+
+        let method = timer.primary_method();
+        let sw = timer.stopwatch_for(method);
+        let splits = timer.splits();
+        let deltas = timer.split_deltas();
+        let name_width = width.saturating_sub(TIMES_WIDTH);
+        let delta_span = |(delta, prev_delta): (Option<f64>, f64)| match delta {
+            Some(delta) => (
+                format!("{:>10}", format_delta(delta)),
+                Some(delta_color(delta, prev_delta)),
+            ),
+            None => (" ".repeat(10), None),
+        };
+        let mut collapsed_until = 0;
+        for (i, s) in splits.iter().enumerate() {
+            let data = match method {
+                TimingMethod::RealTime => &s.1,
+                TimingMethod::GameTime => &s.2,
+            };
+            let group = run_data.group_of(i);
+            if let Some(group) = group.filter(|g| g.range().start == i) {
+                let range = group.range();
+                let group_time: Duration = splits[range.clone()]
+                    .iter()
+                    .map(|s| match method {
+                        TimingMethod::RealTime => s.1.time_elapsed(sw),
+                        TimingMethod::GameTime => s.2.time_elapsed(sw),
+                    })
+                    .sum();
+                lines.push(vec![
+                    (fit(group.name(), name_width), None),
+                    delta_span(deltas[range.end - 1]),
+                    (format!("{:>12}", time_text(group_time)), None),
+                ]);
+                // Only the group with the current split shows its splits
+                if !group.contains(timer.current_split()) {
+                    collapsed_until = range.end;
+                }
+            }
+            if i < collapsed_until {
+                continue;
+            }
+            let name = run_data.get_split_name(s.0).cloned().unwrap_or_default();
+            let name = if group.is_some() {
+                format!("  {name}")
+            } else {
+                name
+            };
+            let current = i == timer.current_split() && timer.is_in_progress();
+            let name_color = current.then_some(Color::Cyan);
+            let time = if data.is_skipped() {
+                (format!("{:>12}", "-"), None)
+            } else {
+                let elapsed = data.time_elapsed(sw);
+                let best_segment = timer.best_segments().get(i).copied().flatten();
+                let gold =
+                    data.is_done() && best_segment.is_none_or(|best| elapsed.as_secs_f64() < best);
+                (
+                    format!("{:>12}", time_text(elapsed)),
+                    gold.then_some(Color::Yellow),
+                )
+            };
+            lines.push(vec![
+                (fit(&name, name_width), name_color),
+                delta_span(deltas[i]),
+                time,
+            ]);
+        }
+
+        lines.push(vec![("-".repeat(width), Some(Color::DarkGrey))]);
+        let sum_of_best = run_data
+            .sum_of_best(method)
+            .map(time_text)
+            .unwrap_or_else(|| String::from("-"));
+        lines.push(vec![
+            (fit("Sum of Best", width.saturating_sub(12)), None),
+            (format!("{sum_of_best:>12}"), None),
+        ]);
+        lines.push(vec![(
+            format!("{} vs {}", method.label(), timer.comparison().label()),
+            Some(Color::DarkGrey),
+        )]);
+        lines.push(Vec::new());
+
+        let timer_color = match timer.phase() {
+            TimerPhase::NotRunning | TimerPhase::Paused => Color::DarkGrey,
+            TimerPhase::Running => Color::Green,
+            TimerPhase::Ended => Color::Cyan,
+        };
+        let timestamp = timer.timestamp().expanded();
+        let big = big_text(&timestamp.simple_text());
+        let big_width = big[0].chars().count() + 4;
+        let pad = " ".repeat(width.saturating_sub(big_width) / 2);
+        for (row, text) in big.into_iter().enumerate() {
+            let mut line = vec![(format!("{pad}{text}"), Some(timer_color))];
+            if row == 4 {
+                line.push((format!(".{}", timestamp.millis_text()), Some(timer_color)));
+            }
+            lines.push(line);
+        }
+        lines.push(Vec::new());
+        lines.push(vec![(fit(&self.help_text(), width), Some(Color::DarkGrey))]);
+        lines.push(vec![(fit(&self.status, width), None)]);
+        lines
+    }
+
+    /// Keys of the bound actions, followed by the ones only the terminal has
+    fn help_text(&self) -> String {
+        let mut parts: Vec<String> = Action::ALL
+            .into_iter()
+            .filter_map(|action| {
+                let binding = self.settings.binding(action)?;
+                Some(format!("{binding} {}", action_help(action)))
+            })
+            .collect();
+        parts.extend(["M method", "Ctrl+S save", "Q quit"].map(String::from));
+        parts.join("  ")
+    }
+}
+
+fn action_help(action: Action) -> &'static str {
+    match action {
+        Action::StartOrSplit => "split",
+        Action::Pause => "start/pause",
+        Action::ToggleGameTime => "game time",
+        Action::Reset => "reset",
+        Action::Undo => "undo",
+        Action::Skip => "skip",
+        Action::NextComparison => "next comparison",
+        Action::PrevComparison => "prev comparison",
+    }
+}
+
+/// Key of the settings for `code`, `None` for keys that can't be bound
+fn key_from_crossterm(code: KeyCode) -> Option<Key> {
+    let key = match code {
+        KeyCode::Char(c) => return Key::from_char(c),
+        KeyCode::F(n) => Key::F(n),
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Esc => Key::Escape,
+        KeyCode::Tab | KeyCode::BackTab => Key::Tab,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Insert => Key::Insert,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Action bound to `key` in the settings. Terminals guess Shift from the case of the
+/// letter typed, which Caps Lock flips too, so a shifted character goes to the binding
+/// without Shift when it has none of its own.
+fn action_for(settings: &Settings, key: &KeyEvent) -> Option<Action> {
+    let binding = KeyBinding {
+        key: key_from_crossterm(key.code)?,
+        ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+        shift: key.modifiers.contains(KeyModifiers::SHIFT) || key.code == KeyCode::BackTab,
+        alt: key.modifiers.contains(KeyModifiers::ALT),
+    };
+    settings.action_for(binding).or_else(|| match binding.key {
+        Key::Char(_) if binding.shift => settings.action_for(KeyBinding {
+            shift: false,
+            ..binding
+        }),
+        _ => None,
+    })
+}
+
+/// Green when ahead of the comparison and red when behind, with the lighter shade when
+/// time was lost or gained respectively compared to the previous split
+fn delta_color(delta: f64, prev_delta: f64) -> Color {
+    let gaining = delta <= prev_delta;
+    match (delta < 0.0, gaining) {
+        (true, true) => Color::Rgb {
+            r: 0,
+            g: 160,
+            b: 60,
+        },
+        (true, false) => Color::Rgb {
+            r: 110,
+            g: 200,
+            b: 130,
+        },
+        (false, true) => Color::Rgb {
+            r: 220,
+            g: 120,
+            b: 110,
+        },
+        (false, false) => Color::Rgb {
+            r: 200,
+            g: 30,
+            b: 30,
+        },
+    }
+}
+
+fn time_text(time: Duration) -> String {
+    let time = ExpandedTimestamp::from(time);
+    format!("{}.{}", time.simple_text(), time.millis_text())
+}
+
+/// Columns the terminal draws `c` across, two for the wide CJK characters and emoji
+/// and none for combining marks
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Longest start of `text` that fits in `width` columns, along with the columns it takes
+fn cut(text: &str, width: usize) -> (&str, usize) {
+    let mut used = 0;
+    for (i, c) in text.char_indices() {
+        let w = char_width(c);
+        if used + w > width {
+            return (&text[..i], used);
+        }
+        used += w;
+    }
+    (text, used)
+}
+
+/// Pads or cuts `text` to take exactly `width` columns
+fn fit(text: &str, width: usize) -> String {
+    let (text, used) = cut(text, width);
+    format!("{text}{}", " ".repeat(width - used))
+}
+
+fn centered(text: &str, width: usize) -> String {
+    let (text, used) = cut(text, width);
+    let left = (width - used) / 2;
+    format!(
+        "{}{text}{}",
+        " ".repeat(left),
+        " ".repeat(width - used - left)
+    )
+}
+
+/// Five rows spelling out digits and colons of `text` in big letters
+fn big_text(text: &str) -> [String; 5] {
+    let mut rows: [String; 5] = Default::default();
+    for c in text.chars() {
+        let glyph = match c.to_digit(10) {
+            Some(digit) => BIG_DIGITS[digit as usize],
+            None if c == ':' => BIG_COLON,
+            None if c == '-' => BIG_MINUS,
+            None => continue,
+        };
+        for (row, part) in rows.iter_mut().zip(glyph) {
+            if !row.is_empty() {
+                row.push(' ');
+            }
+            row.push_str(&part.replace('#', "█"));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use haidomo_core::run_file::write_run_file;
+
+    #[test]
+    fn bind_keys() {
+        let settings = Settings::default();
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let action = |key| action_for(&settings, &key);
+        assert_eq!(Some(Action::StartOrSplit), action(key(KeyCode::Char('s'))));
+        assert_eq!(Some(Action::Pause), action(key(KeyCode::Char(' '))));
+        assert_eq!(Some(Action::PrevComparison), action(key(KeyCode::Left)));
+        assert_eq!(None, action(key(KeyCode::Char('x'))));
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert_eq!(None, action(ctrl_s));
+        // Caps Lock comes through as Shift
+        let shift_s = KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT);
+        assert_eq!(Some(Action::StartOrSplit), action(shift_s));
+
+        let mut settings = Settings::default();
+        settings.set_binding(Action::Reset, KeyBinding::parse("Shift+S"));
+        settings.set_binding(Action::Pause, KeyBinding::parse("F5"));
+        assert_eq!(Some(Action::Reset), action_for(&settings, &shift_s));
+        assert_eq!(
+            Some(Action::StartOrSplit),
+            action_for(&settings, &key(KeyCode::Char('s')))
+        );
+        assert_eq!(
+            Some(Action::Pause),
+            action_for(&settings, &key(KeyCode::F(5)))
+        );
+    }
+
+    #[test]
+    fn wide_text() {
+        assert_eq!("ロックマン  ", fit("ロックマン", 12));
+        assert_eq!("ロック", fit("ロックマン", 7).trim_end());
+        assert_eq!(7, text_columns(&fit("ロックマン", 7)));
+        assert_eq!(" 魔界村 ", centered("魔界村", 8));
+        assert_eq!("Forest", fit("Forest", 6));
+    }
+
+    fn text_columns(text: &str) -> usize {
+        text.chars().map(char_width).sum()
+    }
+
+    #[test]
+    fn big_timer_text() {
+        let rows = big_text("1:02");
+        assert_eq!("  █   ███ ███", rows[0]);
+        assert_eq!("  █ █ █ █   █", rows[1]);
+        assert_eq!("  █ █ █ █ █  ", rows[3]);
+        assert!(rows.iter().all(|row| row.chars().count() == 13));
+        let rows = big_text("-1");
+        assert_eq!("██   █", rows[2]);
+    }
+
+    #[test]
+    fn split_lines() {
+        let names = ["Forest", "Castle"].map(String::from).to_vec();
+        let mut app = TuiApp::new(
+            RunData::new(String::from("Game"), names),
+            None,
+            Settings::default(),
+        );
+        // Space starts the run as well as pausing it
+        app.handle_key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(TimerPhase::Running, app.timer.phase());
+        app.handle_key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(TimerPhase::Paused, app.timer.phase());
+        app.handle_key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(TimerPhase::Running, app.timer.phase());
+        let text: Vec<String> = app
+            .lines(40)
+            .iter()
+            .map(|line| line.iter().map(|(text, _)| text.as_str()).collect())
+            .collect();
+        assert_eq!("Game", text[0].trim());
+        assert!(text[3].starts_with("Forest"));
+        assert!(text[4].starts_with("Castle"));
+    }
+
+    #[test]
+    fn save_imports_next_to_them() {
+        let dir = std::env::temp_dir().join(format!("haidomo-tui-lss-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let run = || RunData::new(String::from("Game"), vec![String::from("Forest")]);
+        let app = TuiApp::new(run(), Some(dir.join("run.lss")), Settings::default());
+        assert_eq!(Some(dir.join("run.bss")), app.file_path);
+
+        write_run_file(&dir.join("run.bss"), &run()).unwrap();
+        let app = TuiApp::new(run(), Some(dir.join("run.lss")), Settings::default());
+        assert_eq!(None, app.file_path);
+        assert!(app.status.contains("won't be saved"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn restore_journaled_run() {
+        let dir = std::env::temp_dir().join(format!("haidomo-tui-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.bss");
+        let names = ["Forest", "Castle"].map(String::from).to_vec();
+        write_run_file(&path, &RunData::new(String::from("Game"), names)).unwrap();
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+
+        // Left without quitting, like a crash
+        let mut app = TuiApp::new(
+            read_run_file(&path).unwrap(),
+            Some(path.clone()),
+            Settings::default(),
+        );
+        app.handle_key(key('s'));
+        app.handle_key(key('s'));
+        app.handle_key(key(' '));
+        let entries = app.journal.as_ref().unwrap().unfinished().unwrap().unwrap();
+        assert_eq!(3, entries.len());
+
+        let mut app = TuiApp::new(
+            read_run_file(&path).unwrap(),
+            Some(path.clone()),
+            Settings::default(),
+        );
+        app.restore_run(&entries, false);
+        assert_eq!(TimerPhase::Paused, app.timer.phase());
+        assert_eq!(1, app.timer.current_split());
+
+        let mut app = TuiApp::new(
+            read_run_file(&path).unwrap(),
+            Some(path.clone()),
+            Settings::default(),
+        );
+        app.restore_run(&entries, true);
+        assert_eq!(TimerPhase::NotRunning, app.timer.phase());
+        let run_data = read_run_file(&path).unwrap();
+        assert_eq!(1, run_data.attempts().len());
+        assert_eq!(1, run_data.attempts()[0].split_times().len());
+        assert!(!app.journal.as_ref().unwrap().path().exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
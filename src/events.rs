@@ -0,0 +1,227 @@
+use haidomo_core::timer::EventKind;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+/// How often the server threads check whether they've been told to stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// State of the timer right after something happened to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimerEvent {
+    pub kind: EventKind,
+    pub time: Duration,
+    pub game_time: Duration,
+    /// Index of the current split, the split count once the run finished
+    pub at: usize,
+    /// Delta against the comparison of every split in seconds, `None` where there's none
+    pub deltas: Vec<Option<f64>>,
+}
+impl TimerEvent {
+    /// Writes the event as a JSON object like
+    /// `{"event":"split","time":12.5,"game_time":12,"at":1,"deltas":[-0.5,null]}`
+    pub fn to_json(&self) -> String {
+        let deltas: Vec<String> = self
+            .deltas
+            .iter()
+            .map(|delta| match delta {
+                Some(delta) if delta.is_finite() => delta.to_string(),
+                _ => String::from("null"),
+            })
+            .collect();
+        format!(
+            r#"{{"event":"{}","time":{},"game_time":{},"at":{},"deltas":[{}]}}"#,
+            self.kind.name(),
+            self.time.as_secs_f64(),
+            self.game_time.as_secs_f64(),
+            self.at,
+            deltas.join(",")
+        )
+    }
+}
+
+/// WebSocket endpoint pushing `TimerEvent`s as JSON text messages to every client
+/// connected, for browser overlays. The server stops once it's dropped.
+pub struct EventServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    clients: Arc<Mutex<Vec<Sender<String>>>>,
+}
+impl EventServer {
+    /// Starts listening on `port` of localhost, or any free port if it's 0
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let port = listener.local_addr()?.port();
+        listener.set_nonblocking(true)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let thread_stop = stop.clone();
+        let thread_clients = clients.clone();
+        thread::spawn(move || accept_clients(listener, thread_clients, thread_stop));
+        Ok(Self {
+            port,
+            stop,
+            clients,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients
+            .lock()
+            .map(|clients| clients.len())
+            .unwrap_or(0)
+    }
+
+    /// Sends `event` to every client, forgetting the ones that hung up
+    pub fn broadcast(&self, event: &TimerEvent) {
+        let json = event.to_json();
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.retain(|client| client.send(json.clone()).is_ok());
+        }
+    }
+}
+impl Drop for EventServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn accept_clients(
+    listener: TcpListener,
+    clients: Arc<Mutex<Vec<Sender<String>>>>,
+    stop: Arc<AtomicBool>,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                println!("[INFO] Event stream client connected from {addr}");
+                let (sender, events) = mpsc::channel();
+                let stop = stop.clone();
+                thread::spawn(move || {
+                    if let Err(err) = handle_client(stream, events, stop) {
+                        eprintln!("[ERROR] Event stream client failed: {err}");
+                    }
+                });
+                if let Ok(mut clients) = clients.lock() {
+                    clients.push(sender);
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(err) => {
+                eprintln!("[ERROR] Event stream failed to accept a client: {err}");
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+    println!("[INFO] Event stream stopped");
+}
+
+fn handle_client(
+    stream: TcpStream,
+    events: Receiver<String>,
+    stop: Arc<AtomicBool>,
+) -> tungstenite::Result<()> {
+    stream.set_nonblocking(false)?;
+    let mut socket = tungstenite::accept(stream).map_err(|err| match err {
+        tungstenite::HandshakeError::Failure(err) => err,
+        tungstenite::HandshakeError::Interrupted(_) => {
+            tungstenite::Error::Io(ErrorKind::WouldBlock.into())
+        }
+    })?;
+    // Reading only has to answer pings and notice the client leaving, so it must not
+    // hold up the events
+    socket.get_mut().set_nonblocking(true)?;
+    while !stop.load(Ordering::Relaxed) {
+        match events.recv_timeout(POLL_INTERVAL) {
+            Ok(json) => ignore_would_block(socket.send(Message::text(json)))?,
+            Err(RecvTimeoutError::Timeout) => {}
+            // The server is gone
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if !read_client(&mut socket)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Takes whatever the client sent, `false` once it closed the connection
+fn read_client(socket: &mut WebSocket<TcpStream>) -> tungstenite::Result<bool> {
+    loop {
+        match socket.read() {
+            Ok(Message::Close(_)) => return Ok(false),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(err)) if err.kind() == ErrorKind::WouldBlock => {
+                return Ok(true);
+            }
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                return Ok(false);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Messages that couldn't be written right away stay queued and go out on the next read
+fn ignore_would_block(result: tungstenite::Result<()>) -> tungstenite::Result<()> {
+    match result {
+        Err(tungstenite::Error::Io(err)) if err.kind() == ErrorKind::WouldBlock => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_event() -> TimerEvent {
+        TimerEvent {
+            kind: EventKind::Split,
+            time: Duration::from_millis(12_500),
+            game_time: Duration::from_secs(12),
+            at: 1,
+            deltas: vec![Some(-0.5), None],
+        }
+    }
+
+    #[test]
+    fn event_json() {
+        assert_eq!(
+            r#"{"event":"split","time":12.5,"game_time":12,"at":1,"deltas":[-0.5,null]}"#,
+            split_event().to_json()
+        );
+    }
+
+    #[test]
+    fn push_events() {
+        let server = EventServer::start(0).expect("Expected to be able to listen on a free port");
+        let (mut client, _) = tungstenite::connect(format!("ws://127.0.0.1:{}", server.port()))
+            .expect("Expected to be able to connect to the server");
+        while server.client_count() == 0 {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let event = split_event();
+        server.broadcast(&event);
+        match client.read().expect("Expected to get the event") {
+            Message::Text(text) => assert_eq!(event.to_json(), text.as_str()),
+            message => panic!("Expected a text message, got {message:?}"),
+        }
+
+        client.close(None).unwrap();
+        // Dropped clients are forgotten on the next broadcast
+        while server.client_count() > 0 {
+            thread::sleep(Duration::from_millis(10));
+            server.broadcast(&event);
+        }
+    }
+}
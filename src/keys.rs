@@ -0,0 +1,57 @@
+use eframe::egui;
+use haidomo_core::settings::{Key, KeyBinding};
+
+/// Key of the settings pressed as `key` in the window, `None` for keys that can't be bound
+pub fn key_from_egui(key: egui::Key) -> Option<Key> {
+    use egui::Key as E;
+    let key = match key {
+        E::ArrowUp => Key::Up,
+        E::ArrowDown => Key::Down,
+        E::ArrowLeft => Key::Left,
+        E::ArrowRight => Key::Right,
+        E::Escape => Key::Escape,
+        E::Tab => Key::Tab,
+        E::Backspace => Key::Backspace,
+        E::Enter => Key::Enter,
+        E::Space => Key::Space,
+        E::Insert => Key::Insert,
+        E::Delete => Key::Delete,
+        E::Home => Key::Home,
+        E::End => Key::End,
+        E::PageUp => Key::PageUp,
+        E::PageDown => Key::PageDown,
+        E::Copy | E::Cut | E::Paste => return None,
+        // The rest are named after the character or the F key they are
+        key => return Key::from_name(key.name()),
+    };
+    Some(key)
+}
+
+/// Binding pressed as `key` with `modifiers` held in the window
+pub fn binding_from_egui(key: egui::Key, modifiers: egui::Modifiers) -> Option<KeyBinding> {
+    Some(KeyBinding {
+        key: key_from_egui(key)?,
+        ctrl: modifiers.ctrl,
+        shift: modifiers.shift,
+        alt: modifiers.alt,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn egui_keys() {
+        assert_eq!(Some(Key::Char('S')), key_from_egui(egui::Key::S));
+        assert_eq!(Some(Key::Char('0')), key_from_egui(egui::Key::Num0));
+        assert_eq!(Some(Key::Char('-')), key_from_egui(egui::Key::Minus));
+        assert_eq!(Some(Key::F(5)), key_from_egui(egui::Key::F5));
+        assert_eq!(Some(Key::Left), key_from_egui(egui::Key::ArrowLeft));
+        assert_eq!(None, key_from_egui(egui::Key::Copy));
+        assert_eq!(
+            KeyBinding::parse("Ctrl+Shift+S"),
+            binding_from_egui(egui::Key::S, egui::Modifiers::CTRL | egui::Modifiers::SHIFT)
+        );
+    }
+}
@@ -1,12 +1,31 @@
-mod stopwatch;
-use stopwatch::*;
+use haidomo_core::action::Action;
+use haidomo_core::journal::{self, Journal, JournalEntry, JournalKind};
+use haidomo_core::livesplit;
+use haidomo_core::run_file::{
+    default_run_data, read_run_file, save_path_for, save_run_file, write_run_file,
+};
+use haidomo_core::settings::Settings;
+use haidomo_core::splits_file::{Comparison, RunData};
+use haidomo_core::stopwatch::{ExpandedTimestamp, Timestamp, TimingMethod};
+use haidomo_core::timer::{format_delta, EventKind, Timer, TimerPhase};
 
-mod splits_file;
-use splits_file::RunData;
+mod widgets;
+use widgets::{ShowSplit, ShowTimestamp};
+
+mod keys;
+use keys::binding_from_egui;
+
+mod server;
+use server::{Command, ControlServer, Request};
+
+mod events;
+use events::{EventServer, TimerEvent};
+
+mod text_output;
+use text_output::{TextOutput, TextState};
 
 use eframe::egui;
-use eframe::egui::Widget;
-use std::fmt::Display;
+use std::path::PathBuf;
 use std::time::Duration;
 
 macro_rules! rich_text {
@@ -37,160 +56,1037 @@ fn main() -> Result<(), eframe::Error> {
         ..Default::default()
     };
 
+    let file_path = std::env::args_os().nth(1).map(PathBuf::from);
+    let run_data = match &file_path {
+        Some(path) if path.exists() => match read_run_file(path) {
+            Ok(run_data) => {
+                println!("[INFO] Loaded splits from {}", path.display());
+                run_data
+            }
+            Err(err) => {
+                eprintln!("[ERROR] Failed to load {}: {err}", path.display());
+                std::process::exit(1);
+            }
+        },
+        _ => default_run_data(),
+    };
+    let file_path = file_path.and_then(|path| open_save_path(&path));
+
     eframe::run_native(
         "Hai Domo!",
         native_options,
-        Box::new(|cc| {
-            let sw = Stopwatch::new();
-            let mut splits = Vec::new();
-            let mut split_names = Vec::new();
-            for i in 1..4 {
-                let name = format!("Split-{:02}", i);
-                let data = StopSplit::new();
-                let split = (i, data);
-                splits.push(split);
-                split_names.push(name);
-            }
-            let run_data = RunData::new(String::from("UrMom"), split_names);
-            Box::new(HaiDomoApp::new_with_splits(cc, sw, run_data))
-        }),
+        Box::new(|cc| Box::new(HaiDomoApp::new(cc, run_data, file_path))),
     )
 }
 
+/// Where to save the run opened from `path`, same as the terminal. Imports from LiveSplit
+/// go to a .bss next to the file so it's never written over.
+fn open_save_path(path: &std::path::Path) -> Option<PathBuf> {
+    let save_path = save_path_for(path);
+    match &save_path {
+        Some(save_path) if save_path != path => {
+            println!("[INFO] Attempts will be saved to {}", save_path.display())
+        }
+        Some(_) => {}
+        None => eprintln!(
+            "[ERROR] {} exists, attempts won't be saved until the run is saved as another file",
+            path.with_extension("bss").display()
+        ),
+    }
+    save_path
+}
+
+/// Green when ahead of the comparison and red when behind, with the lighter shade when
+/// time was lost or gained respectively compared to the previous split
+fn delta_color(delta: f64, prev_delta: f64) -> egui::Color32 {
+    let gaining = delta <= prev_delta;
+    match (delta < 0.0, gaining) {
+        (true, true) => egui::Color32::from_rgb(0, 160, 60),
+        (true, false) => egui::Color32::from_rgb(110, 200, 130),
+        (false, true) => egui::Color32::from_rgb(220, 120, 110),
+        (false, false) => egui::Color32::from_rgb(200, 30, 30),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SplitEdit {
+    Insert(usize),
+    Remove(usize),
+    Swap(usize, usize),
+    AddGroup,
+    RemoveGroup(usize),
+}
+
+#[derive(Clone, Copy)]
+enum PathPrompt {
+    Open,
+    SaveAs,
+    ExportLss,
+    TextOutputDir,
+}
+impl PathPrompt {
+    fn title(&self) -> &'static str {
+        match self {
+            PathPrompt::Open => "Open splits",
+            PathPrompt::SaveAs => "Save splits as",
+            PathPrompt::ExportLss => "Export to LiveSplit",
+            PathPrompt::TextOutputDir => "Write text files to",
+        }
+    }
+}
+
 struct HaiDomoApp {
-    stopwatch: Stopwatch,
-    splits: Vec<(usize, StopSplit)>,
-    run_data: RunData,
-    at: usize,
+    timer: Timer,
+    file_path: Option<PathBuf>,
+    path_prompt: Option<(PathPrompt, String)>,
+    settings: Settings,
+    server: Option<ControlServer>,
+    events: Option<EventServer>,
+    text_output: Option<TextOutput>,
+    /// Journal of the run in progress, when the splits have a file to go next to
+    journal: Option<Journal>,
+    /// Run the journal had left unfinished, waiting on whether to restore it
+    unfinished_run: Option<Vec<JournalEntry>>,
+    run_info_open: bool,
+    split_editor_open: bool,
+    /// Name, first and last split (1-based) of the group about to be added in the editor
+    new_group: (String, usize, usize),
+    keybindings_open: bool,
+    /// Action waiting for a key press to be bound to it
+    rebinding: Option<Action>,
 }
 
 impl HaiDomoApp {
-    fn new(_cc: &eframe::CreationContext<'_>, run_name: String) -> Self {
-        println!("[INFO] Creating HaiDomoApp...");
-        Self {
-            stopwatch: Stopwatch::new(),
-            splits: Vec::new(),
-            run_data: RunData::new(run_name, vec![]),
-            at: 0,
-        }
+    fn new(
+        cc: &eframe::CreationContext<'_>,
+        run_data: RunData,
+        file_path: Option<PathBuf>,
+    ) -> Self {
+        Self::with_settings(&cc.egui_ctx, run_data, file_path, Settings::load())
     }
 
-    fn new_with_splits(
-        _cc: &eframe::CreationContext<'_>,
-        stopwatch: Stopwatch,
+    fn with_settings(
+        ctx: &egui::Context,
         run_data: RunData,
+        file_path: Option<PathBuf>,
+        settings: Settings,
     ) -> Self {
-        let splits: Vec<_> = run_data
-            .get_indexed_split_names()
-            .iter()
-            .map(|(idx, _)| (*idx, StopSplit::new()))
-            .collect();
-        println!("[INFO] Creating HaiDomoApp with {} splits...", splits.len());
-        Self {
-            stopwatch: stopwatch,
-            splits: splits,
-            run_data: run_data,
-            at: 0,
+        let timer = Timer::new(run_data);
+        println!(
+            "[INFO] Creating HaiDomoApp with {} splits...",
+            timer.splits().len()
+        );
+        let mut app = Self {
+            timer,
+            file_path,
+            path_prompt: None,
+            settings,
+            server: None,
+            events: None,
+            text_output: None,
+            journal: None,
+            unfinished_run: None,
+            run_info_open: false,
+            split_editor_open: false,
+            new_group: (String::new(), 1, 1),
+            keybindings_open: false,
+            rebinding: None,
+        };
+        if app.settings.server_enabled {
+            app.start_server(ctx);
+        }
+        if app.settings.events_enabled {
+            app.start_events();
+        }
+        if let Some(dir) = app.settings.text_output_dir.clone() {
+            app.start_text_output(dir);
         }
+        app.open_journal();
+        app
     }
 
-    fn add_split(&mut self, name: String) {
-        match self.run_data.add_split(name) {
-            Ok(i) => {
-                let data = StopSplit::new();
-                let split = (i, data);
-                self.splits.push(split);
+    fn load_run(&mut self, run_data: RunData) {
+        self.abandon_run();
+        self.unfinished_run = None;
+        self.timer.load_run(run_data);
+    }
+
+    fn open_file(&mut self, path: PathBuf) {
+        match read_run_file(&path) {
+            Ok(run_data) => {
+                println!("[INFO] Loaded splits from {}", path.display());
+                self.load_run(run_data);
+                self.file_path = open_save_path(&path);
+                self.open_journal();
             }
-            Err(_) => {
-                eprintln!("[ERROR] Failed to add new split! Max splits reached already?");
+            Err(err) => {
+                eprintln!("[ERROR] Failed to load {}: {err}", path.display());
             }
+        }
+    }
+
+    fn save_file(&mut self) {
+        let Some(path) = &self.file_path else {
+            self.path_prompt = Some((PathPrompt::SaveAs, String::new()));
+            return;
+        };
+        match save_run_file(path, self.timer.run_data(), self.settings.backup_count) {
+            Ok(()) => println!("[INFO] Saved splits to {}", path.display()),
+            Err(err) => eprintln!("[ERROR] Failed to save {}: {err}", path.display()),
+        }
+    }
+
+    fn export_lss(&self, path: PathBuf) {
+        match write_run_file(&path, self.timer.run_data()) {
+            Ok(()) => println!("[INFO] Exported splits to {}", path.display()),
+            Err(err) => eprintln!("[ERROR] Failed to export {}: {err}", path.display()),
+        }
+    }
+
+    fn save_file_as(&mut self, path: PathBuf) {
+        // The run in progress isn't journaled again until the next one starts
+        self.clear_journal();
+        self.journal = Some(Journal::for_run(&path));
+        self.file_path = Some(path);
+        self.save_file();
+    }
+
+    /// Sets up the journal of the splits file, holding on to a run it left unfinished
+    /// to ask whether to restore it
+    fn open_journal(&mut self) {
+        self.journal = self.file_path.as_deref().map(Journal::for_run);
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        match journal.unfinished_of(self.timer.run_data()) {
+            Ok(Some(entries)) => {
+                println!(
+                    "[INFO] Found an unfinished run in {}",
+                    journal.path().display()
+                );
+                self.unfinished_run = Some(entries);
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("[ERROR] Failed to read {}: {err}", journal.path().display()),
+        }
+    }
+
+    fn write_journal(&mut self, kind: JournalKind) {
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        if let Err(err) = journal.record(kind, &self.timer) {
+            eprintln!(
+                "[ERROR] Failed to write to {}: {err}",
+                journal.path().display()
+            );
+        }
+    }
+
+    /// Removes the journal once its run is over, unless it has a run left to restore
+    fn clear_journal(&mut self) {
+        if self.unfinished_run.is_some() {
+            return;
+        }
+        let Some(journal) = &mut self.journal else {
+            return;
         };
+        if let Err(err) = journal.clear() {
+            eprintln!(
+                "[ERROR] Failed to remove {}: {err}",
+                journal.path().display()
+            );
+        }
+    }
+
+    /// Plays the unfinished run from the journal back on the timer, to go on with it or
+    /// to record it as a reset attempt
+    fn restore_run(&mut self, record_as_reset: bool) {
+        let Some(entries) = self.unfinished_run.take() else {
+            return;
+        };
+        if !journal::replay(&mut self.timer, &entries) {
+            return;
+        }
+        // Anything recorded and taken back while playing it is already in the file
+        self.timer.take_attempts_changed();
+        if record_as_reset {
+            let event = self.timer.reset();
+            self.timer_changed(event);
+            self.clear_journal();
+        } else {
+            println!("[INFO] Restored the unfinished run");
+        }
+    }
+
+    /// Handles what a call on the timer did, saving the splits when the run history
+    /// changed and letting the event stream clients know
+    fn timer_changed(&mut self, event: Option<EventKind>) {
+        let Some(kind) = event else {
+            return;
+        };
+        let attempts_changed = self.timer.take_attempts_changed();
+        match kind {
+            EventKind::Pause => println!("[INFO] Stopwatch has been turned off"),
+            EventKind::Resume => println!("[INFO] Stopwatch has been turned on"),
+            EventKind::Finish => println!("[INFO] Recorded finished attempt"),
+            EventKind::Undo if attempts_changed => println!("[INFO] Took back finished attempt"),
+            EventKind::Reset if attempts_changed => println!("[INFO] Recorded reset attempt"),
+            _ => {}
+        }
+        if kind == EventKind::Start && self.unfinished_run.take().is_some() {
+            println!("[INFO] Dropped the unfinished run for a new one");
+        }
+        // The attempt is on disk before the journal says the run is over. A crash in
+        // between leaves a journal of a run that's already the last attempt, which isn't
+        // offered to be restored.
+        if attempts_changed {
+            self.save_attempts();
+        }
+        self.write_journal(kind.into());
+        self.emit(kind);
+    }
+
+    /// Saves the attempts just recorded, when the splits have a file to go to
+    fn save_attempts(&mut self) {
+        if self.file_path.is_some() {
+            self.save_file();
+        }
+    }
+
+    /// Records the run in progress as abandoned, for when it's left without a reset
+    fn abandon_run(&mut self) {
+        if self.timer.abandon() {
+            println!("[INFO] Recorded abandoned attempt");
+            self.timer.take_attempts_changed();
+            self.save_attempts();
+        }
+        self.clear_journal();
+    }
+
+    /// Pushes the timer state to the event stream clients, if the stream is on
+    fn emit(&self, kind: EventKind) {
+        let Some(events) = &self.events else {
+            return;
+        };
+        let deltas = self.timer.split_deltas();
+        events.broadcast(&TimerEvent {
+            kind,
+            time: self
+                .timer
+                .stopwatch_for(TimingMethod::RealTime)
+                .time_elapsed(),
+            game_time: self
+                .timer
+                .stopwatch_for(TimingMethod::GameTime)
+                .time_elapsed(),
+            at: self.timer.current_split(),
+            deltas: deltas.into_iter().map(|(delta, _)| delta).collect(),
+        });
+    }
+
+    fn toggle_game_time_pause(&mut self) {
+        self.timer.toggle_game_time_pause();
+        self.game_time_pause_changed();
+    }
+
+    fn game_time_pause_changed(&mut self) {
+        if self.timer.is_game_time_paused() {
+            println!("[INFO] Game time has been paused");
+            self.write_journal(JournalKind::GameTimePause);
+        } else {
+            println!("[INFO] Game time has been resumed");
+            self.write_journal(JournalKind::GameTimeResume);
+        }
     }
 
-    fn get_split_name(&self, idx: usize) -> Option<&String> {
-        self.run_data.get_split_name(idx)
+    fn run_action(&mut self, ctx: &egui::Context, action: Action) {
+        let event = self.timer.run_action(action);
+        match action {
+            Action::ToggleGameTime if self.timer.is_started() => self.game_time_pause_changed(),
+            Action::NextComparison | Action::PrevComparison => {
+                println!(
+                    "[INFO] Comparing against {}",
+                    self.timer.comparison().label()
+                );
+            }
+            _ => {}
+        }
+        if matches!(event, Some(EventKind::Start | EventKind::Resume)) {
+            ctx.request_repaint();
+        }
+        self.timer_changed(event);
     }
 
-    fn timestamp(&self) -> Timestamp {
-        self.stopwatch.timestamp()
+    fn start_server(&mut self, ctx: &egui::Context) {
+        let port = self.settings.server_port;
+        match ControlServer::start(port, ctx.clone()) {
+            Ok(server) => {
+                println!("[INFO] Control server listening on port {}", server.port());
+                self.server = Some(server);
+            }
+            Err(err) => eprintln!("[ERROR] Failed to start the control server on {port}: {err}"),
+        }
     }
 
-    fn is_started(&self) -> bool {
-        self.stopwatch.is_running() || !self.stopwatch.time_elapsed().is_zero()
+    fn start_events(&mut self) {
+        let port = self.settings.events_port;
+        match EventServer::start(port) {
+            Ok(events) => {
+                println!("[INFO] Event stream listening on port {}", events.port());
+                self.events = Some(events);
+            }
+            Err(err) => eprintln!("[ERROR] Failed to start the event stream on {port}: {err}"),
+        }
     }
 
-    fn is_timer_running(&self) -> bool {
-        self.stopwatch.is_running()
+    fn start_text_output(&mut self, dir: PathBuf) {
+        match TextOutput::new(dir.clone()) {
+            Ok(output) => {
+                println!("[INFO] Writing text files to {}", dir.display());
+                self.text_output = Some(output);
+            }
+            Err(err) => eprintln!(
+                "[ERROR] Failed to write text files to {}: {err}",
+                dir.display()
+            ),
+        }
     }
 
-    fn start_timer(&mut self) {
-        self.stopwatch.clear();
-        for s in self.splits.iter_mut() {
-            let split = &mut s.1;
-            split.clear();
+    /// Writes the current time, split, delta and sum of best out for streaming tools.
+    /// Output stops on the first failure so it doesn't log every frame.
+    fn write_text_output(&mut self, ctx: &egui::Context) {
+        if self.text_output.is_none() {
+            return;
         }
-        self.stopwatch.start();
-        if self.splits.len() >= 1 {
-            let s = &mut self.splits[0];
-            let split = &mut s.1;
-            split.start_at_zero();
+        let timestamp = self.timer.timestamp().expanded();
+        let split_name = self.timer.current_split_name().cloned().unwrap_or_default();
+        // Delta of the running split when it has one, the last split's delta otherwise
+        let deltas = self.timer.split_deltas();
+        let delta = deltas
+            .iter()
+            .take(self.timer.current_split() + 1)
+            .rev()
+            .find_map(|(delta, _)| *delta)
+            .map(format_delta)
+            .unwrap_or_default();
+        let method = self.timer.primary_method();
+        let sum_of_best = match self.timer.run_data().sum_of_best(method) {
+            Some(sob) => {
+                let sob = ExpandedTimestamp::from(sob);
+                format!("{}.{}", sob.simple_text(), sob.millis_text())
+            }
+            None => String::from("-"),
+        };
+        let state = TextState {
+            current_time: format!("{}.{}", timestamp.simple_text(), timestamp.millis_text()),
+            split_name,
+            delta,
+            sum_of_best,
+        };
+        let Some(output) = &mut self.text_output else {
+            return;
+        };
+        match output.write(&state, std::time::Instant::now()) {
+            // A stopped time held back still has to go out once no more frames come
+            Ok(Some(due_in)) => ctx.request_repaint_after(due_in),
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!(
+                    "[ERROR] Stopped writing text files to {}: {err}",
+                    output.dir().display()
+                );
+                self.text_output = None;
+            }
+        }
+    }
+
+    /// Runs the commands that came in through the control server since the last frame
+    fn handle_server_requests(&mut self, ctx: &egui::Context) {
+        while let Some(request) = self.server.as_ref().and_then(|server| server.try_recv()) {
+            self.handle_request(ctx, request);
+        }
+    }
+
+    fn handle_request(&mut self, ctx: &egui::Context, request: Request) {
+        // The unfinished run has to be dealt with before starting another, same as with
+        // the keys, or the new run's journal would take its place
+        let starts = matches!(request.command, Command::StartTimer | Command::StartOrSplit);
+        if starts && self.unfinished_run.is_some() {
+            eprintln!("[ERROR] Ignored a start from the control server, restore or reset the unfinished run first");
+            return;
+        }
+        let event = match &request.command {
+            Command::StartTimer => {
+                if self.timer.is_in_progress() {
+                    return;
+                }
+                self.timer.start()
+            }
+            Command::StartOrSplit => {
+                self.run_action(ctx, Action::StartOrSplit);
+                return;
+            }
+            Command::Split => self.timer.split(),
+            Command::Unsplit => self.timer.undo_split(),
+            Command::SkipSplit => self.timer.skip_split(),
+            Command::Pause => match self.timer.phase() {
+                TimerPhase::Running => self.timer.toggle_pause(),
+                _ => None,
+            },
+            Command::Resume => match self.timer.phase() {
+                TimerPhase::Paused => self.timer.toggle_pause(),
+                _ => None,
+            },
+            Command::Reset => self.timer.reset(),
+            Command::PauseGameTime => {
+                if self.timer.is_started() && !self.timer.is_game_time_paused() {
+                    self.toggle_game_time_pause();
+                }
+                None
+            }
+            Command::UnpauseGameTime => {
+                if self.timer.is_started() && self.timer.is_game_time_paused() {
+                    self.toggle_game_time_pause();
+                }
+                None
+            }
+            Command::SetGameTime(time) => {
+                self.timer.set_game_time(*time);
+                None
+            }
+            Command::SetComparison(name) => {
+                match Comparison::ALL
+                    .into_iter()
+                    .find(|c| c.label().eq_ignore_ascii_case(name))
+                {
+                    Some(comparison) => self.timer.set_comparison(comparison),
+                    None => eprintln!("[ERROR] Unknown comparison `{name}`"),
+                }
+                None
+            }
+            Command::GetCurrentTime => {
+                let time = self.timer.timestamp().seconds();
+                request.reply(livesplit::format_signed_time(time));
+                None
+            }
+            Command::GetSplitIndex => {
+                let index = if self.timer.is_started() {
+                    self.timer.current_split() as i64
+                } else {
+                    -1
+                };
+                request.reply(index.to_string());
+                None
+            }
+            Command::GetCurrentTimerPhase => {
+                request.reply(self.timer.phase().name().to_string());
+                None
+            }
+        };
+        self.timer_changed(event);
+    }
+
+    fn save_settings(&self) {
+        match self.settings.save() {
+            Ok(path) => println!("[INFO] Saved settings to {}", path.display()),
+            Err(err) => eprintln!("[ERROR] Failed to save settings: {err}"),
         }
     }
 
-    fn stop_timer(&mut self) {
-        self.stopwatch.pause();
-        if self.splits.is_empty() {
+    fn show_keybindings(&mut self, ctx: &egui::Context) {
+        if !self.keybindings_open {
             return;
         }
-        for s in self.splits.iter_mut() {
-            let split = &mut s.1;
-            if !split.is_done() {
-                split.stop(&self.stopwatch);
+        if let Some(action) = self.rebinding {
+            let pressed = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+            });
+            match pressed {
+                Some((egui::Key::Escape, _)) => self.rebinding = None,
+                Some((key, modifiers)) => {
+                    // Keys that can't be bound keep waiting on another one
+                    if let Some(binding) = binding_from_egui(key, modifiers) {
+                        self.settings.set_binding(action, Some(binding));
+                        self.rebinding = None;
+                        self.save_settings();
+                    }
+                }
+                None => {}
             }
         }
+
+        let mut open = true;
+        let mut changed = false;
+        egui::Window::new("Keybindings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("keybindings_grid").show(ui, |ui| {
+                    for action in Action::ALL {
+                        ui.label(action.label());
+                        let text = if self.rebinding == Some(action) {
+                            String::from("Press a key...")
+                        } else {
+                            self.settings
+                                .binding(action)
+                                .map(|binding| binding.to_string())
+                                .unwrap_or_else(|| String::from("Unbound"))
+                        };
+                        if ui.button(text).clicked() {
+                            self.rebinding = Some(action);
+                        }
+                        if ui.button("Clear").clicked() {
+                            self.settings.set_binding(action, None);
+                            changed = true;
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.separator();
+                if ui.button("Restore Defaults").clicked() {
+                    self.settings.reset_keybindings();
+                    changed = true;
+                }
+            });
+        if changed {
+            self.rebinding = None;
+            self.save_settings();
+        }
+        if !open {
+            self.keybindings_open = false;
+            self.rebinding = None;
+        }
+    }
+
+    /// Window asking what to do with the run the journal had left unfinished
+    fn show_unfinished_run(&mut self, ctx: &egui::Context) {
+        let Some(last) = self.unfinished_run.as_ref().and_then(|e| e.last()) else {
+            return;
+        };
+        let at = Timestamp::from_secs_f64(last.time).expanded();
+        let mut choice = None;
+        egui::Window::new("Unfinished Run")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("The timer closed at {at} with a run in progress."));
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        choice = Some(false);
+                    }
+                    if ui.button("Record as Reset").clicked() {
+                        choice = Some(true);
+                    }
+                });
+            });
+        if let Some(record_as_reset) = choice {
+            self.restore_run(record_as_reset);
+        }
     }
 
-    fn next_split(&mut self) {
-        self.at += 1;
-        if self.at >= self.splits.len() {
-            self.stop_timer();
+    /// Window to edit the game, category and the rest of the run metadata
+    fn show_run_info(&mut self, ctx: &egui::Context) {
+        if !self.run_info_open {
             return;
         }
+        let mut open = true;
+        let metadata = self.timer.run_data_mut().metadata_mut();
+        egui::Window::new("Run Info")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("run_info_grid").show(ui, |ui| {
+                    for (label, text) in [
+                        ("Game", &mut metadata.game),
+                        ("Category", &mut metadata.category),
+                        ("Platform", &mut metadata.platform),
+                        ("Region", &mut metadata.region),
+                    ] {
+                        ui.label(label);
+                        ui.text_edit_singleline(text);
+                        ui.end_row();
+                    }
+                    ui.label("");
+                    ui.checkbox(&mut metadata.uses_emulator, "Uses Emulator");
+                    ui.end_row();
+                });
+                ui.separator();
+                ui.label("Variables");
+                let mut removed = None;
+                for (i, (name, value)) in metadata.variables.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::TextEdit::singleline(name).desired_width(80.0));
+                        ui.add(egui::TextEdit::singleline(value).desired_width(100.0));
+                        if ui.button("x").clicked() {
+                            removed = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = removed {
+                    metadata.variables.remove(i);
+                }
+                if ui.button("Add Variable").clicked() {
+                    metadata.variables.push((String::new(), String::new()));
+                }
+            });
+        if !open {
+            self.run_info_open = false;
+        }
+    }
+
+    /// Window to edit the game, category and the splits of the run
+    fn show_split_editor(&mut self, ctx: &egui::Context) {
+        if !self.split_editor_open {
+            return;
+        }
+        let mut open = true;
+        let mut edited = false;
+        let running = self.timer.is_in_progress();
+        egui::Window::new("Edit Splits")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if running {
+                    ui.label("Reset the run to edit the splits");
+                    return;
+                }
+                egui::Grid::new("split_editor_metadata").show(ui, |ui| {
+                    let metadata = self.timer.run_data_mut().metadata_mut();
+                    ui.label("Game");
+                    ui.text_edit_singleline(&mut metadata.game);
+                    ui.end_row();
+                    ui.label("Category");
+                    ui.text_edit_singleline(&mut metadata.category);
+                    ui.end_row();
+                    let run_data = self.timer.run_data_mut();
+                    let mut offset = run_data.start_offset();
+                    ui.label("Start Offset");
+                    let drag = egui::DragValue::new(&mut offset)
+                        .speed(0.01)
+                        .max_decimals(3)
+                        .suffix(" s");
+                    if ui.add(drag).changed() {
+                        run_data.set_start_offset(offset);
+                    }
+                    ui.end_row();
+                });
+                ui.separator();
+
+                let run_data = self.timer.run_data_mut();
+                let splits_count = run_data.split_names().len();
+                let mut action = None;
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for i in 0..splits_count {
+                            ui.horizontal(|ui| {
+                                let mut name = run_data.split_names()[i].clone();
+                                if ui.text_edit_singleline(&mut name).changed() {
+                                    run_data.rename_split(i, name);
+                                }
+                                if ui.add_enabled(i > 0, egui::Button::new("^")).clicked() {
+                                    action = Some(SplitEdit::Swap(i, i - 1));
+                                }
+                                let is_last = i + 1 == splits_count;
+                                if ui.add_enabled(!is_last, egui::Button::new("v")).clicked() {
+                                    action = Some(SplitEdit::Swap(i, i + 1));
+                                }
+                                if ui.button("+").on_hover_text("Insert below").clicked() {
+                                    action = Some(SplitEdit::Insert(i + 1));
+                                }
+                                if ui.button("x").on_hover_text("Remove").clicked() {
+                                    action = Some(SplitEdit::Remove(i));
+                                }
+                            });
+                        }
+                    });
+                if ui.button("Add Split").clicked() {
+                    action = Some(SplitEdit::Insert(splits_count));
+                }
+
+                ui.separator();
+                ui.label("Groups");
+                for g in 0..run_data.groups().len() {
+                    ui.horizontal(|ui| {
+                        let group = &run_data.groups()[g];
+                        let range = group.range();
+                        let mut name = group.name().to_string();
+                        let resp =
+                            ui.add(egui::TextEdit::singleline(&mut name).desired_width(120.0));
+                        if resp.changed() {
+                            run_data.rename_group(g, name);
+                        }
+                        ui.label(format!("Splits {}-{}", range.start + 1, range.end));
+                        if ui.button("x").on_hover_text("Ungroup").clicked() {
+                            action = Some(SplitEdit::RemoveGroup(g));
+                        }
+                    });
+                }
+                if splits_count > 0 {
+                    ui.horizontal(|ui| {
+                        let (name, first, last) = &mut self.new_group;
+                        ui.add(egui::TextEdit::singleline(name).desired_width(120.0));
+                        ui.add(egui::DragValue::new(first).clamp_range(1..=splits_count));
+                        ui.label("-");
+                        ui.add(egui::DragValue::new(last).clamp_range(*first..=splits_count));
+                        if ui.button("Add Group").clicked() {
+                            action = Some(SplitEdit::AddGroup);
+                        }
+                    });
+                }
+
+                match action {
+                    Some(SplitEdit::Insert(i)) => {
+                        let name = format!("Split-{:02}", splits_count + 1);
+                        if run_data.insert_split(i, name).is_err() {
+                            eprintln!("[ERROR] Failed to insert split at {i}");
+                        }
+                    }
+                    Some(SplitEdit::Remove(i)) => {
+                        if let Some(name) = run_data.remove_split(i) {
+                            println!("[INFO] Removed split {name}");
+                        }
+                    }
+                    Some(SplitEdit::Swap(a, b)) => run_data.swap_splits(a, b),
+                    Some(SplitEdit::AddGroup) => {
+                        let (name, first, last) = &self.new_group;
+                        let len = (last + 1).saturating_sub(*first);
+                        if run_data.add_group(name.clone(), first - 1, len).is_err() {
+                            eprintln!("[ERROR] Groups can't be empty or overlap other groups");
+                        }
+                    }
+                    Some(SplitEdit::RemoveGroup(g)) => {
+                        run_data.remove_group(g);
+                    }
+                    None => {}
+                }
+                edited = action.is_some();
+            });
+        if edited {
+            self.timer.splits_edited();
+        }
+        if !open {
+            self.split_editor_open = false;
+        }
+    }
 
-        let prev = &mut self.splits.get_mut(self.at - 1).unwrap().1;
-        prev.stop(&self.stopwatch);
-        let next = &mut self.splits.get_mut(self.at).unwrap().1;
-        next.start(&self.stopwatch);
+    fn show_path_prompt(&mut self, ctx: &egui::Context) {
+        let Some((prompt, path_text)) = &mut self.path_prompt else {
+            return;
+        };
+        let prompt = *prompt;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(prompt.title())
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let resp = ui.text_edit_singleline(path_text);
+                if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    confirmed = true;
+                }
+                ui.horizontal(|ui| {
+                    confirmed |= ui.button("Ok").clicked();
+                    cancelled = ui.button("Cancel").clicked();
+                });
+            });
+
+        if cancelled {
+            self.path_prompt = None;
+        } else if confirmed && !path_text.trim().is_empty() {
+            let path = PathBuf::from(path_text.trim());
+            self.path_prompt = None;
+            match prompt {
+                PathPrompt::Open => self.open_file(path),
+                PathPrompt::SaveAs => self.save_file_as(path),
+                PathPrompt::ExportLss => self.export_lss(path.with_extension("lss")),
+                PathPrompt::TextOutputDir => {
+                    self.start_text_output(path.clone());
+                    self.settings.text_output_dir = Some(path);
+                    self.save_settings();
+                }
+            }
+        }
     }
 }
 
 impl eframe::App for HaiDomoApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.abandon_run();
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if self.stopwatch.is_running() {
+        if self.timer.is_running() {
             ctx.request_repaint();
         }
-        let timestamp = self.timestamp().expanded();
+        self.handle_server_requests(ctx);
+        self.write_text_output(ctx);
+        let timestamp = self.timer.timestamp().expanded();
+
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("Open...").clicked() {
+                        self.path_prompt = Some((PathPrompt::Open, String::new()));
+                        ui.close_menu();
+                    }
+                    if ui.button("Save").clicked() {
+                        self.save_file();
+                        ui.close_menu();
+                    }
+                    if ui.button("Save As...").clicked() {
+                        let path_text = self
+                            .file_path
+                            .as_ref()
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_default();
+                        self.path_prompt = Some((PathPrompt::SaveAs, path_text));
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Run Info...").clicked() {
+                        self.run_info_open = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Edit Splits...").clicked() {
+                        self.split_editor_open = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Export LiveSplit...").clicked() {
+                        self.path_prompt = Some((PathPrompt::ExportLss, String::new()));
+                        ui.close_menu();
+                    }
+                });
+                ui.menu_button("Timing", |ui| {
+                    for method in [TimingMethod::RealTime, TimingMethod::GameTime] {
+                        let selected = self.timer.primary_method() == method;
+                        if ui.radio(selected, method.label()).clicked() {
+                            self.timer.set_primary_method(method);
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    for comparison in Comparison::ALL {
+                        let selected = self.timer.comparison() == comparison;
+                        if ui.radio(selected, comparison.label()).clicked() {
+                            self.timer.set_comparison(comparison);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.menu_button("Settings", |ui| {
+                    if ui.button("Keybindings...").clicked() {
+                        self.keybindings_open = true;
+                        ui.close_menu();
+                    }
+                    let port = self.settings.server_port;
+                    let label = format!("Control Server (port {port})");
+                    if ui
+                        .checkbox(&mut self.settings.server_enabled, label)
+                        .clicked()
+                    {
+                        if self.settings.server_enabled {
+                            self.start_server(ctx);
+                        } else {
+                            self.server = None;
+                        }
+                        self.save_settings();
+                        ui.close_menu();
+                    }
+                    let port = self.settings.events_port;
+                    let label = match &self.events {
+                        Some(events) => format!(
+                            "Event Stream (port {port}, {} clients)",
+                            events.client_count()
+                        ),
+                        None => format!("Event Stream (port {port})"),
+                    };
+                    if ui
+                        .checkbox(&mut self.settings.events_enabled, label)
+                        .clicked()
+                    {
+                        if self.settings.events_enabled {
+                            self.start_events();
+                        } else {
+                            self.events = None;
+                        }
+                        self.save_settings();
+                        ui.close_menu();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Daily Backups to Keep");
+                        let drag = egui::DragValue::new(&mut self.settings.backup_count)
+                            .clamp_range(0..=100);
+                        if ui.add(drag).changed() {
+                            self.save_settings();
+                        }
+                    });
+                    let mut text_output = self.settings.text_output_dir.is_some();
+                    if ui.checkbox(&mut text_output, "Text File Output").clicked() {
+                        if text_output {
+                            self.path_prompt = Some((PathPrompt::TextOutputDir, String::new()));
+                        } else {
+                            self.text_output = None;
+                            self.settings.text_output_dir = None;
+                            self.save_settings();
+                        }
+                        ui.close_menu();
+                    }
+                });
+            });
+        });
+        self.show_path_prompt(ctx);
+        self.show_keybindings(ctx);
+        self.show_unfinished_run(ctx);
 
         egui::TopBottomPanel::top("run_title").show(ctx, |ui| {
-            ui.heading("Ur Mom");
-            let inner_response = ui.horizontal(|ui| ui.label("Any%"));
-            let sense = egui::Sense::click().union(egui::Sense::hover());
-            let innr = inner_response.inner;
-            let resp = inner_response.response;
-            let resp = ui.interact(resp.rect, resp.id, sense.clone());
-            let bgrs = ui.interact_bg(sense.clone());
-            if resp.double_clicked() || innr.double_clicked() {
-                println!("[INFO] Double clicked area");
-            } else if bgrs.double_clicked() {
-                if let Some(mouse) = ui.input(|i| i.pointer.interact_pos()) {
-                    if resp.rect.top() < mouse.y && mouse.y < resp.rect.bottom() {
-                        println!("[INFO] Double clicked lower background");
-                    } else {
-                        println!("[INFO] Double clicked upper background");
+            let metadata = self.timer.run_data().metadata();
+            let resp = ui
+                .vertical_centered(|ui| {
+                    ui.heading(&metadata.game);
+                    let mut category = metadata.category.clone();
+                    let variables: Vec<&str> = metadata
+                        .variables
+                        .iter()
+                        .map(|(_, value)| value.as_str())
+                        .filter(|value| !value.is_empty())
+                        .collect();
+                    if !variables.is_empty() {
+                        category.push_str(&format!(" ({})", variables.join(", ")));
                     }
-                }
+                    ui.label(category);
+                    let mut details: Vec<&str> = [&metadata.platform, &metadata.region]
+                        .into_iter()
+                        .map(String::as_str)
+                        .filter(|detail| !detail.is_empty())
+                        .collect();
+                    if metadata.uses_emulator {
+                        details.push("Emulator");
+                    }
+                    if !details.is_empty() {
+                        ui.small(details.join(" | "));
+                    }
+                })
+                .response;
+            let resp = ui.interact(resp.rect, resp.id, egui::Sense::click());
+            if resp.double_clicked() {
+                self.run_info_open = true;
             }
         });
+        self.show_run_info(ctx);
+        self.show_split_editor(ctx);
 
         egui::CentralPanel::default().show(ctx, |ui| {
             let max_rect = ui.max_rect();
@@ -199,18 +1095,88 @@ impl eframe::App for HaiDomoApp {
                 let max_rect = ui.max_rect();
                 ui.set_width(max_rect.width());
                 ui.vertical_centered_justified(|ui| {
-                    for s in self.splits.iter() {
-                        let name = self.get_split_name(*&s.0).unwrap();
-                        let data = &s.1;
+                    let method = self.timer.primary_method();
+                    let sw = self.timer.stopwatch_for(method);
+                    let run_data = self.timer.run_data();
+                    let splits = self.timer.splits();
+                    // Worked out ahead so group headers can show the delta of their last split
+                    let deltas = self.timer.split_deltas();
+                    let show_delta =
+                        |ui: &mut egui::Ui, (delta, prev_delta): (Option<f64>, f64)| {
+                            match delta {
+                                Some(delta) => ui.label(
+                                    rich_text!(format_delta(delta))
+                                        .monospace()
+                                        .color(delta_color(delta, prev_delta)),
+                                ),
+                                None => ui.label(rich_text!("      ").monospace()),
+                            };
+                        };
+
+                    let mut collapsed_until = 0;
+                    for (i, s) in splits.iter().enumerate() {
+                        let data = match method {
+                            TimingMethod::RealTime => &s.1,
+                            TimingMethod::GameTime => &s.2,
+                        };
+                        let group = run_data.group_of(i);
+                        if let Some(group) = group.filter(|g| g.range().start == i) {
+                            let range = group.range();
+                            let group_time: Duration = splits[range.clone()]
+                                .iter()
+                                .map(|s| match method {
+                                    TimingMethod::RealTime => s.1.time_elapsed(sw),
+                                    TimingMethod::GameTime => s.2.time_elapsed(sw),
+                                })
+                                .sum();
+                            ui.horizontal(|ui| {
+                                // Display: $group | delta of its last split | combined time
+                                ui.label(rich_text!(group.name()).monospace().strong());
+                                ui.separator();
+                                show_delta(ui, deltas[range.end - 1]);
+                                ui.separator();
+                                ExpandedTimestamp::from(group_time).show(ui, 16.0, 10.0);
+                            });
+                            // Only the group with the current split shows its splits
+                            if !group.contains(self.timer.current_split()) {
+                                collapsed_until = range.end;
+                            }
+                        }
+                        if i < collapsed_until {
+                            continue;
+                        }
+                        let name = run_data.get_split_name(s.0).unwrap();
                         ui.horizontal(|ui| {
-                            // Display: $name | split-data
+                            // Display: $name | delta | split-data
+                            if group.is_some() {
+                                ui.add_space(12.0);
+                            }
                             ui.label(rich_text!(name).monospace());
                             ui.separator();
-                            data.show(ui, &self.stopwatch);
+                            show_delta(ui, deltas[i]);
+                            ui.separator();
+                            let best_segment = self
+                                .timer
+                                .best_segments()
+                                .get(i)
+                                .copied()
+                                .flatten()
+                                .map(Duration::from_secs_f64);
+                            data.show(ui, sw, best_segment);
                         });
                     }
                 });
             });
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(rich_text!("Sum of Best").monospace());
+                ui.separator();
+                let method = self.timer.primary_method();
+                match self.timer.run_data().sum_of_best(method) {
+                    Some(sob) => ExpandedTimestamp::from(sob).show(ui, 16.0, 10.0),
+                    None => ui.label(rich_text!("-").monospace()),
+                };
+            });
         });
 
         egui::TopBottomPanel::bottom("current_time")
@@ -222,18 +1188,82 @@ impl eframe::App for HaiDomoApp {
             .show(ctx, |ui| {
                 timestamp.show(ui, 64.0, 32.0);
 
-                if ui.input(|i| i.key_pressed(egui::Key::Space)) {
-                    if !self.is_started() {
-                        self.start_timer();
-                    } else if self.stopwatch.toggle() {
-                        println!("[INFO] Stopwatch has been turned on");
-                        ctx.request_repaint();
-                    } else {
-                        println!("[INFO] Stopwatch has been turned off");
-                    }
-                } else if ui.input(|i| i.key_pressed(egui::Key::S)) {
-                    self.next_split();
+                // Don't steal keys from text fields like the path prompt
+                if ctx.wants_keyboard_input() {
+                    return;
+                }
+                // Keys pressed while rebinding only go to the keybindings window
+                if self.keybindings_open {
+                    return;
+                }
+                // The unfinished run has to be dealt with before starting another
+                if self.unfinished_run.is_some() {
+                    return;
+                }
+                let action = ui.input(|i| {
+                    i.events.iter().find_map(|event| match event {
+                        egui::Event::Key {
+                            key,
+                            pressed: true,
+                            repeat: false,
+                            modifiers,
+                            ..
+                        } => binding_from_egui(*key, *modifiers)
+                            .and_then(|binding| self.settings.action_for(binding)),
+                        _ => None,
+                    })
+                });
+                if let Some(action) = action {
+                    self.run_action(ctx, action);
                 }
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use haidomo_core::journal::JournalKind;
+
+    #[test]
+    fn server_waits_on_unfinished_run() {
+        let dir = std::env::temp_dir().join(format!("haidomo-app-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("run.bss");
+        let names = ["Forest", "Castle"].map(String::from).to_vec();
+        write_run_file(&path, &RunData::new(String::from("Game"), names)).unwrap();
+
+        // Left in the middle of a run, like a crash
+        let mut timer = Timer::new(read_run_file(&path).unwrap());
+        let mut journal = Journal::for_run(&path);
+        timer.start();
+        journal.record(EventKind::Start.into(), &timer).unwrap();
+        timer.split();
+        journal
+            .record(JournalKind::Event(EventKind::Split), &timer)
+            .unwrap();
+
+        let ctx = egui::Context::default();
+        let mut app = HaiDomoApp::with_settings(
+            &ctx,
+            read_run_file(&path).unwrap(),
+            Some(path.clone()),
+            Settings::default(),
+        );
+        assert!(app.unfinished_run.is_some());
+        for command in [Command::StartTimer, Command::StartOrSplit] {
+            app.handle_request(&ctx, Request::without_reply(command));
+            assert_eq!(TimerPhase::NotRunning, app.timer.phase());
+        }
+        assert!(app.unfinished_run.is_some());
+        let entries = Journal::for_run(&path).unfinished().unwrap().unwrap();
+        assert_eq!(2, entries.len());
+
+        // Once it's dealt with the server can start the next one
+        app.restore_run(true);
+        app.handle_request(&ctx, Request::without_reply(Command::StartTimer));
+        assert_eq!(TimerPhase::Running, app.timer.phase());
+        assert_eq!(1, read_run_file(&path).unwrap().attempts().len());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
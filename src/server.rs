@@ -0,0 +1,290 @@
+use eframe::egui;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How often the server threads check whether they've been told to stop
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a client waits on the app to answer a query before giving up on it
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Commands of the LiveSplit Server protocol, one per line with arguments after a space
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    StartTimer,
+    StartOrSplit,
+    Split,
+    Unsplit,
+    SkipSplit,
+    Pause,
+    Resume,
+    Reset,
+    PauseGameTime,
+    UnpauseGameTime,
+    SetGameTime(Duration),
+    SetComparison(String),
+    GetCurrentTime,
+    GetSplitIndex,
+    GetCurrentTimerPhase,
+}
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (name, arg) = match line.split_once(' ') {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (line, ""),
+        };
+        let command = match name.to_ascii_lowercase().as_str() {
+            "starttimer" => Command::StartTimer,
+            "startorsplit" => Command::StartOrSplit,
+            "split" => Command::Split,
+            "unsplit" => Command::Unsplit,
+            "skipsplit" => Command::SkipSplit,
+            "pause" => Command::Pause,
+            "resume" => Command::Resume,
+            "reset" => Command::Reset,
+            "pausegametime" => Command::PauseGameTime,
+            "unpausegametime" => Command::UnpauseGameTime,
+            "setgametime" => Command::SetGameTime(
+                parse_time(arg).ok_or_else(|| format!("Invalid game time `{arg}`"))?,
+            ),
+            "setcomparison" => Command::SetComparison(arg.to_string()),
+            "getcurrenttime" => Command::GetCurrentTime,
+            "getsplitindex" => Command::GetSplitIndex,
+            "getcurrenttimerphase" => Command::GetCurrentTimerPhase,
+            _ => return Err(format!("Unknown command `{name}`")),
+        };
+        Ok(command)
+    }
+
+    /// Whether the client waits on an answer for the command
+    pub fn is_query(&self) -> bool {
+        matches!(
+            self,
+            Command::GetCurrentTime | Command::GetSplitIndex | Command::GetCurrentTimerPhase
+        )
+    }
+}
+
+/// Parses times sent by clients, anything from `83.5` to `1:02:03.25`
+fn parse_time(text: &str) -> Option<Duration> {
+    let mut parts = text.rsplit(':');
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let mut total = seconds;
+    for multiplier in [60.0, 3600.0] {
+        if let Some(part) = parts.next() {
+            total += part.parse::<u64>().ok()? as f64 * multiplier;
+        }
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+/// A command from a client, queries have to be answered through `reply`
+pub struct Request {
+    pub command: Command,
+    reply: Option<Sender<String>>,
+}
+impl Request {
+    /// Request from a client that isn't waiting on an answer
+    #[cfg(test)]
+    pub fn without_reply(command: Command) -> Self {
+        Self {
+            command,
+            reply: None,
+        }
+    }
+
+    pub fn reply(self, answer: String) {
+        if let Some(reply) = self.reply {
+            // The client might have hung up already
+            let _ = reply.send(answer);
+        }
+    }
+}
+
+/// TCP listener taking LiveSplit Server commands on its own threads. The app picks the
+/// requests up with `try_recv` each frame, and the server stops once it's dropped.
+pub struct ControlServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    requests: Receiver<Request>,
+}
+impl ControlServer {
+    /// Starts listening on `port` of localhost, or any free port if it's 0. `ctx` gets a
+    /// repaint requested whenever a command comes in so the app handles it without
+    /// waiting on input.
+    pub fn start(port: u16, ctx: egui::Context) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let port = listener.local_addr()?.port();
+        listener.set_nonblocking(true)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, requests) = mpsc::channel();
+        let thread_stop = stop.clone();
+        thread::spawn(move || accept_clients(listener, sender, ctx, thread_stop));
+        Ok(Self {
+            port,
+            stop,
+            requests,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn try_recv(&self) -> Option<Request> {
+        self.requests.try_recv().ok()
+    }
+}
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn accept_clients(
+    listener: TcpListener,
+    sender: Sender<Request>,
+    ctx: egui::Context,
+    stop: Arc<AtomicBool>,
+) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, addr)) => {
+                println!("[INFO] Control server client connected from {addr}");
+                let sender = sender.clone();
+                let ctx = ctx.clone();
+                let stop = stop.clone();
+                thread::spawn(move || {
+                    if let Err(err) = handle_client(stream, sender, ctx, stop) {
+                        eprintln!("[ERROR] Control server client failed: {err}");
+                    }
+                });
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(err) => {
+                eprintln!("[ERROR] Control server failed to accept a client: {err}");
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+    println!("[INFO] Control server stopped");
+}
+
+fn handle_client(
+    stream: TcpStream,
+    sender: Sender<Request>,
+    ctx: egui::Context,
+    stop: Arc<AtomicBool>,
+) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        // Timing out keeps what was read so far in `line` for the next go
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                continue;
+            }
+            Err(err) => return Err(err),
+        }
+        let text = String::from_utf8_lossy(&line).into_owned();
+        line.clear();
+        if text.trim().is_empty() {
+            continue;
+        }
+        let command = match Command::parse(&text) {
+            Ok(command) => command,
+            Err(err) => {
+                eprintln!("[ERROR] Control server: {err}");
+                continue;
+            }
+        };
+        let (reply, answer) = if command.is_query() {
+            let (reply, answer) = mpsc::channel();
+            (Some(reply), Some(answer))
+        } else {
+            (None, None)
+        };
+        if sender.send(Request { command, reply }).is_err() {
+            // The app is gone
+            break;
+        }
+        ctx.request_repaint();
+        if let Some(answer) = answer {
+            match answer.recv_timeout(REPLY_TIMEOUT) {
+                Ok(answer) => writer.write_all(format!("{answer}\r\n").as_bytes())?,
+                Err(_) => eprintln!("[ERROR] Control server: the timer didn't answer in time"),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Ok(Command::StartTimer), Command::parse("starttimer\r\n"));
+        assert_eq!(Ok(Command::Split), Command::parse("SPLIT"));
+        assert_eq!(
+            Ok(Command::SetGameTime(Duration::from_millis(83_500))),
+            Command::parse("setgametime 1:23.5")
+        );
+        assert_eq!(
+            Ok(Command::SetComparison("Best Segments".into())),
+            Command::parse("setcomparison Best Segments")
+        );
+        assert!(Command::parse("setgametime soon").is_err());
+        assert!(Command::parse("dance").is_err());
+        assert!(Command::GetSplitIndex.is_query());
+        assert!(!Command::Reset.is_query());
+    }
+
+    #[test]
+    fn answer_queries() {
+        let server = ControlServer::start(0, egui::Context::default())
+            .expect("Expected to be able to listen on a free port");
+        let mut client = TcpStream::connect(("127.0.0.1", server.port()))
+            .expect("Expected to be able to connect to the server");
+        client.write_all(b"split\r\ngetsplitindex\r\n").unwrap();
+
+        let mut commands = Vec::new();
+        while commands.len() < 2 {
+            match server.try_recv() {
+                Some(request) => {
+                    commands.push(request.command.clone());
+                    if request.command.is_query() {
+                        request.reply(String::from("1"));
+                    }
+                }
+                None => thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        assert_eq!(vec![Command::Split, Command::GetSplitIndex], commands);
+        let mut answer = String::new();
+        BufReader::new(client).read_line(&mut answer).unwrap();
+        assert_eq!("1\r\n", answer);
+    }
+
+    #[test]
+    fn parse_times() {
+        assert_eq!(Some(Duration::from_millis(1500)), parse_time("1.5"));
+        assert_eq!(Some(Duration::from_secs(3723)), parse_time("1:02:03"));
+        assert_eq!(None, parse_time("1:2:3:4"));
+        assert_eq!(None, parse_time("-5"));
+    }
+}
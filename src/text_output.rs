@@ -0,0 +1,133 @@
+use haidomo_core::run_file::replace_file;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How often the current time gets written while it's running, 10 times a second
+pub const CURRENT_TIME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Names of the files written, in the order of the `TextState` fields
+pub const FILE_NAMES: [&str; 4] = [
+    "current_time.txt",
+    "split_name.txt",
+    "delta.txt",
+    "sum_of_best.txt",
+];
+
+/// Timer state as it's written out, each field going to its own file
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextState {
+    pub current_time: String,
+    pub split_name: String,
+    pub delta: String,
+    pub sum_of_best: String,
+}
+impl TextState {
+    fn texts(&self) -> [&str; 4] {
+        [
+            &self.current_time,
+            &self.split_name,
+            &self.delta,
+            &self.sum_of_best,
+        ]
+    }
+}
+
+/// Keeps plain text files in a directory up to date with the timer for streaming tools
+/// to read, a file is only rewritten when its text changed. Files are replaced whole so a
+/// tool never reads one halfway through a write, but they aren't synced to disk as
+/// they're of no use after a crash.
+pub struct TextOutput {
+    dir: PathBuf,
+    written: [Option<String>; FILE_NAMES.len()],
+    /// When the current time was last written, it changes every frame while running
+    current_time_at: Option<Instant>,
+}
+impl TextOutput {
+    /// Writes to `dir`, creating it when it doesn't exist yet
+    pub fn new(dir: PathBuf) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            written: Default::default(),
+            current_time_at: None,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes the files whose text changed, the current time at most once per
+    /// `CURRENT_TIME_INTERVAL`. Returns how long until a current time held back this time
+    /// is due, `None` when everything was written.
+    pub fn write(&mut self, state: &TextState, now: Instant) -> std::io::Result<Option<Duration>> {
+        let mut due_in = None;
+        for (i, ((name, text), written)) in FILE_NAMES
+            .iter()
+            .zip(state.texts())
+            .zip(self.written.iter_mut())
+            .enumerate()
+        {
+            if written.as_deref() == Some(text) {
+                continue;
+            }
+            if i == 0 {
+                let next = self
+                    .current_time_at
+                    .map(|at| at + CURRENT_TIME_INTERVAL)
+                    .filter(|next| *next > now);
+                if let Some(next) = next {
+                    due_in = Some(next - now);
+                    continue;
+                }
+                self.current_time_at = Some(now);
+            }
+            replace_file(&self.dir.join(name), text.as_bytes())?;
+            *written = Some(text.to_string());
+        }
+        Ok(due_in)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_text_files() {
+        let dir = std::env::temp_dir().join(format!("haidomo-text-{}", std::process::id()));
+        let mut output = TextOutput::new(dir.clone()).expect("Expected to create the directory");
+        let mut state = TextState {
+            current_time: String::from("01:02.345"),
+            split_name: String::from("Forest"),
+            delta: String::from("-1.25"),
+            sum_of_best: String::from("12:34.567"),
+        };
+        let start = Instant::now();
+        assert_eq!(None, output.write(&state, start).unwrap());
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!("01:02.345", read("current_time.txt"));
+        assert_eq!("Forest", read("split_name.txt"));
+        assert_eq!("-1.25", read("delta.txt"));
+        assert_eq!("12:34.567", read("sum_of_best.txt"));
+
+        // The current time waits for its interval, the rest goes out right away
+        state.current_time = String::from("01:02.361");
+        state.split_name = String::from("Castle");
+        state.delta.clear();
+        let later = start + Duration::from_millis(16);
+        assert_eq!(
+            Some(CURRENT_TIME_INTERVAL - Duration::from_millis(16)),
+            output.write(&state, later).unwrap()
+        );
+        assert_eq!("Castle", read("split_name.txt"));
+        assert_eq!("", read("delta.txt"));
+        assert_eq!("01:02.345", read("current_time.txt"));
+
+        let later = start + CURRENT_TIME_INTERVAL;
+        assert_eq!(None, output.write(&state, later).unwrap());
+        assert_eq!("01:02.361", read("current_time.txt"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
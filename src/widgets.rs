@@ -0,0 +1,83 @@
+use eframe::egui;
+use haidomo_core::stopwatch::{ExpandedTimestamp, StopSplit, Stopwatch};
+use std::time::Duration;
+
+/// Drawing of the timer types, which don't know about egui themselves
+pub trait ShowTimestamp {
+    fn show(&self, ui: &mut egui::Ui, main_size: f32, millis_size: f32) -> egui::Response;
+
+    /// Like `show` but with every part of the time painted with `color` when given
+    fn show_with_color(
+        &self,
+        ui: &mut egui::Ui,
+        main_size: f32,
+        millis_size: f32,
+        color: Option<egui::Color32>,
+    ) -> egui::Response;
+}
+impl ShowTimestamp for ExpandedTimestamp {
+    fn show(&self, ui: &mut egui::Ui, main_size: f32, millis_size: f32) -> egui::Response {
+        self.show_with_color(ui, main_size, millis_size, None)
+    }
+
+    fn show_with_color(
+        &self,
+        ui: &mut egui::Ui,
+        main_size: f32,
+        millis_size: f32,
+        color: Option<egui::Color32>,
+    ) -> egui::Response {
+        let hours_minutes_seconds = egui::RichText::new(self.simple_text())
+            .monospace()
+            .color(color.unwrap_or(egui::Color32::BLACK))
+            .line_height(Some(main_size - 2.0))
+            .size(main_size);
+
+        let mut milliseconds = egui::RichText::new(format!(".{}", self.millis_text()))
+            .monospace()
+            .size(millis_size);
+        if let Some(color) = color {
+            milliseconds = milliseconds.color(color);
+        }
+        let inner_response = ui.with_layout(
+            egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
+            |ui| {
+                let style = egui::Style::default();
+                let mut job = egui::text::LayoutJob::default();
+                hours_minutes_seconds.append_to(
+                    &mut job,
+                    &style,
+                    egui::FontSelection::Default,
+                    egui::Align::BOTTOM,
+                );
+                milliseconds.append_to(
+                    &mut job,
+                    &style,
+                    egui::FontSelection::Default,
+                    egui::Align::BOTTOM,
+                );
+
+                ui.label(job)
+            },
+        );
+
+        inner_response.inner
+    }
+}
+
+pub trait ShowSplit {
+    /// Shows the split time, in gold once the split is done if it beat `best_segment`
+    fn show(&self, ui: &mut egui::Ui, sw: &Stopwatch, best_segment: Option<Duration>);
+}
+impl ShowSplit for StopSplit {
+    fn show(&self, ui: &mut egui::Ui, sw: &Stopwatch, best_segment: Option<Duration>) {
+        if self.is_skipped() {
+            ui.label(egui::RichText::new("-").monospace());
+            return;
+        }
+        let elapsed = self.time_elapsed(sw);
+        let gold = self.is_done() && best_segment.is_none_or(|best| elapsed < best);
+        let color = gold.then_some(egui::Color32::GOLD);
+        ExpandedTimestamp::from(elapsed).show_with_color(ui, 16.0, 10.0, color);
+    }
+}